@@ -0,0 +1,312 @@
+//! Time buckets, bucket iterators and half-open time ranges.
+
+use super::{DateTimeUtc, DurationMicros, UnixTimeMicros, UnixTimestampXlf};
+use ::chrono::TimeDelta;
+use ::std::fmt;
+
+/// Unit to truncate a time to, in UTC.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TimeUnit {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    /// A week starts on Monday, as ISO 8601.
+    Week,
+}
+
+impl TimeUnit {
+    /// Get the length of the unit in microseconds.
+    pub fn micros(self) -> DurationMicros {
+        match self {
+            Self::Second => 1_000_000,
+            Self::Minute => 60 * 1_000_000,
+            Self::Hour => 60 * 60 * 1_000_000,
+            Self::Day => 24 * 60 * 60 * 1_000_000,
+            Self::Week => 7 * 24 * 60 * 60 * 1_000_000,
+        }
+    }
+
+    /// Get the time which buckets of the unit are aligned to,
+    /// the Unix epoch or Monday 1969-12-29 for weeks.
+    pub fn origin(self) -> UnixTimeMicros {
+        match self {
+            Self::Week => -3 * Self::Day.micros(),
+            _ => 0,
+        }
+    }
+
+    /// Get the name of the unit, e.g. `hour`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Second => "second",
+            Self::Minute => "minute",
+            Self::Hour => "hour",
+            Self::Day => "day",
+            Self::Week => "week",
+        }
+    }
+}
+
+impl fmt::Display for TimeUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Extension for bucketing `DateTimeUtc` and `UnixTimeMicros`.
+///
+/// # Examples
+///
+/// ```
+/// use xelf::datetime::*;
+///
+/// let t = utc_from_str("2024-03-07T10:35:12.5Z").unwrap();
+/// assert_eq!(t.truncate_to(TimeUnit::Hour), utc_from_str("2024-03-07T10:00:00Z").unwrap());
+/// assert_eq!(t.truncate_to(TimeUnit::Week), utc_from_str("2024-03-04T00:00:00Z").unwrap());
+/// assert_eq!(
+///     t.truncate_by(15 * TimeUnit::Minute.micros()),
+///     utc_from_str("2024-03-07T10:30:00Z").unwrap()
+/// );
+/// ```
+pub trait TimeBucketXlf: Copy + Ord {
+    /// Truncate to a multiple of `step` since `origin`, it's unchanged if `step` is not positive.
+    fn truncate_from(&self, origin: UnixTimeMicros, step: DurationMicros) -> Self;
+
+    /// Add microseconds, or `None` on overflow.
+    fn checked_add_micros(&self, micros: DurationMicros) -> Option<Self>;
+
+    /// Truncate to the start of a minute, an hour, a day or a week in UTC.
+    #[inline]
+    fn truncate_to(&self, unit: TimeUnit) -> Self {
+        self.truncate_from(unit.origin(), unit.micros())
+    }
+
+    /// Truncate to a multiple of `step` since the Unix epoch, e.g. 15 minutes.
+    #[inline]
+    fn truncate_by(&self, step: DurationMicros) -> Self {
+        self.truncate_from(0, step)
+    }
+}
+
+fn truncate_micros(micros: i64, origin: i64, step: i64) -> i64 {
+    if step <= 0 {
+        return micros;
+    }
+    let rem = (micros as i128 - origin as i128).rem_euclid(step as i128);
+    micros.saturating_sub(rem as i64)
+}
+
+impl TimeBucketXlf for UnixTimeMicros {
+    #[inline]
+    fn truncate_from(&self, origin: UnixTimeMicros, step: DurationMicros) -> Self {
+        truncate_micros(*self, origin, step)
+    }
+
+    #[inline]
+    fn checked_add_micros(&self, micros: DurationMicros) -> Option<Self> {
+        self.checked_add(micros)
+    }
+}
+
+impl TimeBucketXlf for DateTimeUtc {
+    fn truncate_from(&self, origin: UnixTimeMicros, step: DurationMicros) -> Self {
+        if step <= 0 {
+            return *self;
+        }
+        truncate_micros(self.timestamp_micros(), origin, step).micros_as_unix_timestamp()
+    }
+
+    #[inline]
+    fn checked_add_micros(&self, micros: DurationMicros) -> Option<Self> {
+        self.checked_add_signed(TimeDelta::microseconds(micros))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Iterator of the times from `start` in steps before `end`, see [`bucket_range`].
+#[derive(Clone, Debug)]
+pub struct BucketRange<T> {
+    next: Option<T>,
+    end: T,
+    step: DurationMicros,
+}
+
+impl<T: TimeBucketXlf> Iterator for BucketRange<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let time = self.next.filter(|x| *x < self.end)?;
+        self.next = time.checked_add_micros(self.step);
+        Some(time)
+    }
+}
+
+/// Iterate over the times from `start` in steps of `step` microseconds, before `end`.
+/// It's empty if `step` is not positive.
+///
+/// # Examples
+///
+/// ```
+/// use xelf::datetime::*;
+///
+/// let start = utc_from_str("2024-03-07T10:35:00Z").unwrap().truncate_to(TimeUnit::Hour);
+/// let end = utc_from_str("2024-03-07T13:00:00Z").unwrap();
+/// let hours: Vec<_> = bucket_range(start, end, TimeUnit::Hour.micros())
+///     .map(utc_into_str)
+///     .collect();
+/// assert_eq!(
+///     hours,
+///     [
+///         "2024-03-07T10:00:00.000000Z",
+///         "2024-03-07T11:00:00.000000Z",
+///         "2024-03-07T12:00:00.000000Z",
+///     ]
+/// );
+/// ```
+pub fn bucket_range<T: TimeBucketXlf>(start: T, end: T, step: DurationMicros) -> BucketRange<T> {
+    BucketRange {
+        next: (step > 0).then_some(start),
+        end,
+        step,
+    }
+}
+
+/// Half-open time range `[start, end)`, which is empty if `start >= end`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TimeRange<T = DateTimeUtc> {
+    pub start: T,
+    pub end: T,
+}
+
+/// Half-open range of Unix timestamps in microseconds.
+pub type TimeRangeMicros = TimeRange<UnixTimeMicros>;
+
+impl<T: TimeBucketXlf> TimeRange<T> {
+    /// Create a range `[start, end)`.
+    #[inline]
+    pub fn new(start: T, end: T) -> Self {
+        Self { start, end }
+    }
+
+    /// Check if the range has no time.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    /// Check if a time is in the range.
+    #[inline]
+    pub fn contains(&self, time: &T) -> bool {
+        self.start <= *time && *time < self.end
+    }
+
+    /// Check if another range is entirely in the range, an empty range is in any range.
+    pub fn contains_range(&self, other: &Self) -> bool {
+        other.is_empty() || (self.start <= other.start && other.end <= self.end)
+    }
+
+    /// Check if the ranges share any time.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.start.max(other.start) < self.end.min(other.end)
+    }
+
+    /// Get the time shared by the ranges, or `None` if they don't overlap.
+    pub fn intersect(&self, other: &Self) -> Option<Self> {
+        let range = Self::new(self.start.max(other.start), self.end.min(other.end));
+        (!range.is_empty()).then_some(range)
+    }
+
+    /// Extend the range to the buckets of a unit which it touches.
+    pub fn align_to(&self, unit: TimeUnit) -> Self {
+        let start = self.start.truncate_to(unit);
+        let end = self.end.truncate_to(unit);
+        let end = if end < self.end {
+            end.checked_add_micros(unit.micros()).unwrap_or(self.end)
+        } else {
+            end
+        };
+        Self::new(start, end)
+    }
+
+    /// Iterate over the times from the start in steps, see [`bucket_range`].
+    #[inline]
+    pub fn buckets(&self, step: DurationMicros) -> BucketRange<T> {
+        bucket_range(self.start, self.end, step)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::datetime::*;
+
+    fn utc(s: &str) -> DateTimeUtc {
+        utc_from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_time_bucket() {
+        let t = utc("2024-03-10T10:35:12.345678901Z");
+        let cases = [
+            (TimeUnit::Second, "2024-03-10T10:35:12Z"),
+            (TimeUnit::Minute, "2024-03-10T10:35:00Z"),
+            (TimeUnit::Hour, "2024-03-10T10:00:00Z"),
+            (TimeUnit::Day, "2024-03-10T00:00:00Z"),
+            (TimeUnit::Week, "2024-03-04T00:00:00Z"),
+        ];
+        for (unit, v) in cases {
+            assert_eq!(t.truncate_to(unit), utc(v), "{}", unit);
+            let micros = t.timestamp_micros();
+            assert_eq!(micros.truncate_to(unit), utc(v).timestamp_micros());
+        }
+        assert_eq!(
+            utc("2024-03-11T00:00:00Z").truncate_to(TimeUnit::Week),
+            utc("2024-03-11T00:00:00Z")
+        );
+        assert_eq!(
+            utc("1969-12-31T23:59:59Z").truncate_to(TimeUnit::Day),
+            utc("1969-12-31T00:00:00Z")
+        );
+        assert_eq!(t.truncate_by(0), t);
+        assert_eq!((-1).truncate_by(1_000_000), -1_000_000);
+
+        let start = utc("2024-03-10T00:00:00Z");
+        let end = utc("2024-03-10T01:00:00Z");
+        let step = 20 * TimeUnit::Minute.micros();
+        assert_eq!(bucket_range(start, end, step).count(), 3);
+        assert_eq!(bucket_range(start, end, 0).count(), 0);
+        assert_eq!(bucket_range(end, start, step).count(), 0);
+        let v: Vec<_> = bucket_range(0, 10, 4).collect();
+        assert_eq!(v, [0, 4, 8]);
+        assert_eq!(bucket_range(i64::MAX - 1, i64::MAX, 4).count(), 1);
+    }
+
+    #[test]
+    fn test_time_range() {
+        let a = TimeRange::new(utc("2024-03-10T00:00:00Z"), utc("2024-03-10T12:00:00Z"));
+        let b = TimeRange::new(utc("2024-03-10T06:00:00Z"), utc("2024-03-11T00:00:00Z"));
+        let c = TimeRange::new(a.end, b.end);
+        assert!(!a.is_empty() && TimeRange::new(a.end, a.start).is_empty());
+        assert!(a.contains(&a.start) && !a.contains(&a.end));
+        assert!(a.overlaps(&b) && b.overlaps(&a));
+        assert!(!a.overlaps(&c));
+        assert_eq!(a.intersect(&b), Some(TimeRange::new(b.start, a.end)));
+        assert_eq!(a.intersect(&c), None);
+        assert!(b.contains_range(&c) && !a.contains_range(&b));
+        assert!(a.contains_range(&TimeRange::new(b.end, b.end)));
+        assert_eq!(
+            TimeRange::new(utc("2024-03-10T06:30:00Z"), utc("2024-03-10T08:00:00.5Z"))
+                .align_to(TimeUnit::Hour),
+            TimeRange::new(utc("2024-03-10T06:00:00Z"), utc("2024-03-10T09:00:00Z"))
+        );
+        assert_eq!(a.buckets(TimeUnit::Hour.micros()).count(), 12);
+
+        let m: TimeRangeMicros = TimeRange::new(0, 100);
+        assert_eq!(
+            m.intersect(&TimeRange::new(50, 150)),
+            Some(TimeRange::new(50, 100))
+        );
+        assert_eq!(m.buckets(30).collect::<Vec<_>>(), [0, 30, 60, 90]);
+    }
+}
@@ -0,0 +1,1158 @@
+use ::chrono::{prelude::*, TimeDelta};
+use std::time::Duration;
+
+mod bucket;
+mod parser;
+mod schedule;
+#[cfg(feature = "tz")]
+mod tz;
+
+pub use bucket::*;
+pub use parser::*;
+pub use schedule::*;
+#[cfg(feature = "tz")]
+pub use tz::*;
+
+#[cfg(feature = "sea-orm")]
+pub use ::sea_orm::prelude::DateTimeUtc;
+#[cfg(not(feature = "sea-orm"))]
+pub type DateTimeUtc = DateTime<Utc>;
+
+/// Unix timestamp in microseconds
+pub type UnixTimeMicros = i64;
+
+/// Duration in microseconds
+pub type DurationMicros = i64;
+
+pub trait UnixTimestampXlf: Sized {
+    /// Convert days into microseconds.
+    fn micros_from_days(&self) -> Self;
+
+    /// Convert hours into microseconds.
+    fn micros_from_hours(&self) -> Self;
+
+    /// Convert minutes into microseconds.
+    fn micros_from_mins(&self) -> Self;
+
+    /// Convert seconds into microseconds.
+    fn micros_from_secs(&self) -> Self;
+
+    /// Convert seconds into microseconds.
+    fn micros_from_secs_f64(secs: f64) -> Self;
+
+    /// Convert milliseconds into microseconds.
+    fn micros_from_millis(&self) -> Self;
+
+    /// Convert the UNIX timestamp in milliseconds into `DateTimeUtc`.
+    fn micros_as_unix_timestamp(&self) -> DateTimeUtc;
+
+    /// Convert the UNIX timestamp in milliseconds into `DateTimeUtc` with error.
+    fn micros_as_unix_timestamp_opt(&self) -> chrono::LocalResult<DateTimeUtc>;
+
+    /// Get the current UNIX timestamp in microseconds.
+    fn micros_now() -> Self;
+
+    /// Get the UNIX timestamp from a UTC string or a timestamp in microseconds.
+    fn micros_from_utc_str(s: impl AsRef<str>) -> Option<Self>;
+
+    /// Convert into a UTC string.
+    fn micros_into_utc_str(&self) -> String;
+}
+
+/// Get the default value for `DateTime<Utc>`, the Unix Epoch at 1970-01-01T00:00:00Z.
+#[inline]
+pub fn utc_default() -> DateTimeUtc {
+    Utc.timestamp_opt(0, 0).unwrap()
+}
+
+/// Parses an RFC 3339 date-and-time string into a `DateTimeUtc` value.
+pub fn utc_from_str(s: &str) -> chrono::ParseResult<DateTimeUtc> {
+    DateTime::parse_from_rfc3339(s).map(DateTime::<Utc>::from)
+}
+
+/// Convert a local date and time into a zone, where an ambiguous time is the earlier one,
+/// and a time in a gap is shifted forward by the length of the gap.
+pub(crate) fn zoned_from_local<Z: TimeZone>(tz: &Z, local: NaiveDateTime) -> DateTime<Z> {
+    match tz.from_local_datetime(&local) {
+        chrono::LocalResult::Single(x) => x,
+        chrono::LocalResult::Ambiguous(earlier, _) => earlier,
+        chrono::LocalResult::None => {
+            // The offset before the gap.
+            let offset = tz
+                .offset_from_utc_datetime(&(local - TimeDelta::days(1)))
+                .fix();
+            let utc = local - TimeDelta::seconds(offset.local_minus_utc() as i64);
+            tz.from_utc_datetime(&utc)
+        }
+    }
+}
+
+/// Convert a `DateTimeUtc` value into an RFC 3339 date-and-time string
+/// with the format `YYYY-MM-DDTHH:MM:SS.SSSSSSZ`.
+pub fn utc_into_str(utc: DateTimeUtc) -> String {
+    utc.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+impl UnixTimestampXlf for UnixTimeMicros {
+    #[inline]
+    fn micros_from_days(&self) -> Self {
+        self * (24 * 60 * 60 * 1_000_000)
+    }
+
+    #[inline]
+    fn micros_from_hours(&self) -> Self {
+        self * (60 * 60 * 1_000_000)
+    }
+
+    #[inline]
+    fn micros_from_mins(&self) -> Self {
+        self * (60 * 1_000_000)
+    }
+
+    #[inline]
+    fn micros_from_secs(&self) -> Self {
+        self * 1_000_000
+    }
+
+    #[inline]
+    fn micros_from_secs_f64(secs: f64) -> Self {
+        (secs * 1_000_000.0) as Self
+    }
+
+    fn micros_from_millis(&self) -> Self {
+        self * 1_000
+    }
+
+    fn micros_as_unix_timestamp(&self) -> DateTimeUtc {
+        self.micros_as_unix_timestamp_opt()
+            .single()
+            .unwrap_or_else(utc_default)
+    }
+
+    fn micros_as_unix_timestamp_opt(&self) -> chrono::LocalResult<DateTimeUtc> {
+        let (mut secs, mut micros) = (self / 1_000_000, self % 1_000_000);
+        if micros < 0 {
+            secs -= 1;
+            micros += 1_000_000;
+        }
+        Utc.timestamp_opt(secs, micros as u32 * 1_000)
+    }
+
+    fn micros_now() -> Self {
+        Utc::now().timestamp_micros()
+    }
+
+    fn micros_from_utc_str(s: impl AsRef<str>) -> Option<Self> {
+        let s = s.as_ref();
+        s.parse::<UnixTimeMicros>().ok().or_else(|| {
+            DateTime::parse_from_rfc3339(s)
+                .ok()
+                .map(|x| DateTime::<Utc>::from(x).timestamp_micros())
+        })
+    }
+
+    fn micros_into_utc_str(&self) -> String {
+        utc_into_str(self.micros_as_unix_timestamp())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Module to serialize and deserialize a **`DateTimeUtc`**
+#[cfg(feature = "serde")]
+pub mod serde_x_utc {
+    use super::*;
+    use ::serde::{
+        de::{self, Unexpected},
+        ser::Serializer,
+    };
+    use ::std::fmt;
+
+    pub(crate) struct DeUtcVisitor;
+
+    impl<'de> de::Visitor<'de> for DeUtcVisitor {
+        type Value = DateTime<Utc>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "a formatted date and time string")
+        }
+
+        // from seconds
+        fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            match Utc.timestamp_millis_opt(value as i64).single() {
+                Some(v) => Ok(v),
+                _ => Err(de::Error::invalid_type(Unexpected::Unsigned(value), &self)),
+            }
+        }
+
+        // from seconds
+        fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            match Utc.timestamp_millis_opt(value).single() {
+                Some(v) => Ok(v),
+                _ => Err(de::Error::invalid_type(Unexpected::Signed(value), &self)),
+            }
+        }
+
+        // from seconds as float
+        fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            let secs = value.floor();
+            match Utc
+                .timestamp_opt(secs as i64, ((value - secs) * 1000000000.) as u32)
+                .single()
+            {
+                Some(v) => Ok(v),
+                _ => Err(de::Error::invalid_type(Unexpected::Float(value), &self)),
+            }
+        }
+
+        fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            match DateTime::parse_from_rfc3339(value) {
+                Ok(v) => Ok(DateTime::<Utc>::from(v)),
+                _ => Err(de::Error::invalid_type(Unexpected::Str(value), &self)),
+            }
+        }
+    }
+
+    struct DeUtcMicrosVisitor;
+
+    impl<'de> de::Visitor<'de> for DeUtcMicrosVisitor {
+        type Value = UnixTimeMicros;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "a formatted date and time string")
+        }
+
+        // from microseconds
+        fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            UnixTimeMicros::try_from(value)
+                .map_err(|_| de::Error::invalid_type(Unexpected::Unsigned(value), &self))
+        }
+
+        // from microseconds
+        fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Ok(value)
+        }
+
+        // from microseconds as float
+        fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Ok(value as i64)
+        }
+
+        fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            match DateTime::parse_from_rfc3339(value) {
+                Ok(v) => Ok(DateTime::<Utc>::from(v).timestamp_micros()),
+                _ => Err(de::Error::invalid_type(Unexpected::Str(value), &self)),
+            }
+        }
+    }
+
+    /// Function to serializing a **`DateTimeUtc`**
+    pub fn serialize<S>(utc: &DateTimeUtc, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&utc_into_str(*utc))
+    }
+
+    /// Function to deserializing a **`DateTimeUtc`**
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<DateTimeUtc, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(DeUtcVisitor)
+    }
+
+    pub mod f64 {
+        use super::*;
+
+        /// Function to serializing a **`DateTimeUtc`**
+        pub fn serialize<S>(utc: &DateTimeUtc, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.serialize_f64(utc.timestamp_micros() as f64 / 1000000.)
+        }
+
+        /// Function to deserializing a **`DateTimeUtc`**
+        pub fn deserialize<'de, D>(deserializer: D) -> Result<DateTimeUtc, D::Error>
+        where
+            D: de::Deserializer<'de>,
+        {
+            deserializer.deserialize_f64(DeUtcVisitor)
+        }
+    }
+
+    pub mod micros {
+        use super::*;
+
+        /// Function to serializing a **`DateTimeUtc`**
+        pub fn serialize<S>(micros: UnixTimeMicros, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.serialize_i64(micros)
+        }
+
+        /// Function to deserializing a **`DateTimeUtc`**
+        pub fn deserialize<'de, D>(deserializer: D) -> Result<UnixTimeMicros, D::Error>
+        where
+            D: de::Deserializer<'de>,
+        {
+            deserializer.deserialize_i64(DeUtcMicrosVisitor)
+        }
+    }
+
+    /// Deserialize by the default [`UtcParser`], which also accepts RFC 2822, times without
+    /// an offset, dates and epoch timestamps of any unit.
+    pub mod parsed {
+        use super::*;
+
+        /// Function to serializing a **`DateTimeUtc`**
+        pub fn serialize<S>(utc: &DateTimeUtc, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            super::serialize(utc, serializer)
+        }
+
+        /// Function to deserializing a **`DateTimeUtc`**
+        pub fn deserialize<'de, D>(deserializer: D) -> Result<DateTimeUtc, D::Error>
+        where
+            D: de::Deserializer<'de>,
+        {
+            DefaultUtcParser::parser().deserialize(deserializer)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+pub use serde_x_utc::{deserialize as de_x_utc, serialize as ser_x_utc};
+#[cfg(feature = "serde")]
+pub use serde_x_utc::{f64::deserialize as de_x_utc_f64, f64::serialize as ser_x_utc_f64};
+#[cfg(feature = "serde")]
+pub use serde_x_utc::{
+    micros::deserialize as de_x_utc_micros, micros::serialize as ser_x_utc_micros,
+};
+#[cfg(feature = "serde")]
+pub use serde_x_utc::{
+    parsed::deserialize as de_x_utc_parsed, parsed::serialize as ser_x_utc_parsed,
+};
+
+/// Adapter for `#[serde_as]` to serialize and deserialize a **`DateTimeUtc`** like [`serde_x_utc`].
+///
+/// It also applies to `Option<DateTimeUtc>`, where null and an empty string are `None`,
+/// and composes inside `Vec`, `HashMap` and so on.
+#[cfg(feature = "serde_with")]
+pub struct XUtc;
+
+#[cfg(feature = "serde_with")]
+impl<'de> serde_with::DeserializeAs<'de, DateTimeUtc> for XUtc {
+    fn deserialize_as<D>(deserializer: D) -> Result<DateTimeUtc, D::Error>
+    where
+        D: ::serde::Deserializer<'de>,
+    {
+        serde_x_utc::deserialize(deserializer)
+    }
+}
+
+#[cfg(feature = "serde_with")]
+impl<'de> serde_with::DeserializeAs<'de, Option<DateTimeUtc>> for XUtc {
+    fn deserialize_as<D>(deserializer: D) -> Result<Option<DateTimeUtc>, D::Error>
+    where
+        D: ::serde::Deserializer<'de>,
+    {
+        use crate::serde::DeOptionVisitor;
+        deserializer.deserialize_any(DeOptionVisitor(serde_x_utc::DeUtcVisitor))
+    }
+}
+
+#[cfg(feature = "serde_with")]
+impl serde_with::SerializeAs<DateTimeUtc> for XUtc {
+    fn serialize_as<S>(utc: &DateTimeUtc, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        serde_x_utc::serialize(utc, serializer)
+    }
+}
+
+#[cfg(feature = "serde_with")]
+impl serde_with::SerializeAs<Option<DateTimeUtc>> for XUtc {
+    fn serialize_as<S>(utc: &Option<DateTimeUtc>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        match utc {
+            Some(v) => serde_x_utc::serialize(v, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+const NANOS_PER_SEC: i128 = 1_000_000_000;
+
+/// Units of humanized durations in nanoseconds, from the largest to the smallest.
+const DURATION_UNITS: [(&str, i128); 7] = [
+    ("d", 24 * 60 * 60 * NANOS_PER_SEC),
+    ("h", 60 * 60 * NANOS_PER_SEC),
+    ("m", 60 * NANOS_PER_SEC),
+    ("s", NANOS_PER_SEC),
+    ("ms", 1_000_000),
+    ("us", 1_000),
+    ("ns", 1),
+];
+
+/// Parse a duration string into a `Duration`, it accepts:
+/// - humanized durations, e.g. `1500ms`, `2h`, `1d 2h 3m 4.5s`, `1 hour 30 mins`;
+/// - ISO-8601 durations of weeks, days and time, e.g. `PT5M`, `P1DT2H`, `PT0.25S`;
+/// - seconds as a number, e.g. `90`, `1.5`.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use xelf::datetime::duration_from_str;
+///
+/// assert_eq!(duration_from_str("1500ms"), Some(Duration::from_millis(1500)));
+/// assert_eq!(duration_from_str("PT5M"), Some(Duration::from_secs(300)));
+/// assert_eq!(duration_from_str("1h 30m"), Some(Duration::from_secs(5400)));
+/// assert_eq!(duration_from_str("-1s"), None);
+/// ```
+pub fn duration_from_str(s: &str) -> Option<Duration> {
+    let nanos = parse_duration_nanos(s)?;
+    if nanos < 0 {
+        return None;
+    }
+    let secs = u64::try_from(nanos / NANOS_PER_SEC).ok()?;
+    Some(Duration::new(secs, (nanos % NANOS_PER_SEC) as u32))
+}
+
+/// Convert a `Duration` into a humanized string, e.g. `1d 2h 3m 4s 500ms`.
+pub fn duration_into_str(duration: Duration) -> String {
+    format_duration_nanos(duration.as_nanos() as i128)
+}
+
+/// Parse a humanized or ISO-8601 duration, or seconds as a number, into nanoseconds.
+pub(crate) fn parse_duration_nanos(s: &str) -> Option<i128> {
+    let s = s.trim();
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(v) => (true, v),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+    let nanos = if let Some(v) = s.strip_prefix(['P', 'p']) {
+        parse_iso_duration(v)?
+    } else if s.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+        parse_human_duration(s)?
+    } else {
+        return None;
+    };
+    Some(if negative { -nanos } else { nanos })
+}
+
+/// Format nanoseconds into a humanized duration, e.g. `-1d 2h 3m 4s 500ms`.
+pub(crate) fn format_duration_nanos(nanos: i128) -> String {
+    if nanos == 0 {
+        return "0s".to_owned();
+    }
+    let mut rest = nanos.unsigned_abs();
+    let mut parts = Vec::new();
+    for (unit, n) in DURATION_UNITS {
+        let v = rest / n as u128;
+        rest %= n as u128;
+        if v > 0 {
+            parts.push(format!("{}{}", v, unit));
+        }
+    }
+    let s = parts.join(" ");
+    if nanos < 0 {
+        format!("-{}", s)
+    } else {
+        s
+    }
+}
+
+/// Format nanoseconds into an ISO-8601 duration of days and time, e.g. `-P1DT2H3M4.5S`.
+pub(crate) fn format_iso_duration_nanos(nanos: i128) -> String {
+    let (d, h, m, sec) = (
+        DURATION_UNITS[0].1 as u128,
+        DURATION_UNITS[1].1 as u128,
+        DURATION_UNITS[2].1 as u128,
+        DURATION_UNITS[3].1 as u128,
+    );
+    let mut rest = nanos.unsigned_abs();
+    let mut s = String::from(if nanos < 0 { "-P" } else { "P" });
+    let days = rest / d;
+    rest %= d;
+    if days > 0 {
+        s.push_str(&format!("{}D", days));
+    }
+    if rest > 0 || days == 0 {
+        s.push('T');
+        let (hours, mins) = (rest / h, rest % h / m);
+        let (secs, frac) = (rest % m / sec, rest % sec);
+        if hours > 0 {
+            s.push_str(&format!("{}H", hours));
+        }
+        if mins > 0 {
+            s.push_str(&format!("{}M", mins));
+        }
+        if secs > 0 || frac > 0 || rest == 0 {
+            s.push_str(&secs.to_string());
+            if frac > 0 {
+                let frac = format!("{:09}", frac);
+                s.push('.');
+                s.push_str(frac.trim_end_matches('0'));
+            }
+            s.push('S');
+        }
+    }
+    s
+}
+
+/// Scale a decimal number such as `1.25` by a unit in nanoseconds.
+fn scale_duration(num: &str, unit: i128) -> Option<i128> {
+    let (int, frac) = num.split_once('.').unwrap_or((num, ""));
+    if (int.is_empty() && frac.is_empty())
+        || !int.bytes().chain(frac.bytes()).all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    let mut nanos = match int {
+        "" => 0,
+        _ => int.parse::<i128>().ok()?.checked_mul(unit)?,
+    };
+    let mut scale = unit;
+    for c in frac.bytes().take(18) {
+        scale /= 10;
+        nanos = nanos.checked_add((c - b'0') as i128 * scale)?;
+    }
+    Some(nanos)
+}
+
+fn parse_human_duration(s: &str) -> Option<i128> {
+    if let Some(nanos) = scale_duration(s, NANOS_PER_SEC) {
+        return Some(nanos);
+    }
+    let mut total = 0i128;
+    let mut rest = s;
+    while !rest.is_empty() {
+        let end = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let (num, tail) = rest.split_at(end);
+        let tail = tail.trim_start();
+        let end = tail
+            .find(|c: char| !c.is_alphabetic())
+            .unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(end);
+        let unit = match unit.to_ascii_lowercase().as_str() {
+            "d" | "day" | "days" => DURATION_UNITS[0].1,
+            "h" | "hr" | "hrs" | "hour" | "hours" => DURATION_UNITS[1].1,
+            "m" | "min" | "mins" | "minute" | "minutes" => DURATION_UNITS[2].1,
+            "s" | "sec" | "secs" | "second" | "seconds" => DURATION_UNITS[3].1,
+            "ms" | "msec" | "millis" | "millisecond" | "milliseconds" => DURATION_UNITS[4].1,
+            "us" | "µs" | "usec" | "micros" | "microsecond" | "microseconds" => {
+                DURATION_UNITS[5].1
+            }
+            "ns" | "nsec" | "nanos" | "nanosecond" | "nanoseconds" => DURATION_UNITS[6].1,
+            "w" | "week" | "weeks" => 7 * DURATION_UNITS[0].1,
+            _ => return None,
+        };
+        total = total.checked_add(scale_duration(num, unit)?)?;
+        rest = tail.trim_start();
+    }
+    Some(total)
+}
+
+fn parse_iso_duration(s: &str) -> Option<i128> {
+    let (date, time) = s.split_once(['T', 't']).unwrap_or((s, ""));
+    if (date.is_empty() && time.is_empty()) || s.ends_with(['T', 't']) {
+        return None;
+    }
+    let (d, h, m, sec) = (
+        DURATION_UNITS[0].1,
+        DURATION_UNITS[1].1,
+        DURATION_UNITS[2].1,
+        DURATION_UNITS[3].1,
+    );
+    let date = parse_iso_fields(date, &[('W', 7 * d), ('D', d)])?;
+    let time = parse_iso_fields(time, &[('H', h), ('M', m), ('S', sec)])?;
+    date.checked_add(time)
+}
+
+/// Parse fields such as `1H30M`, the designators must be in the order of `units`.
+fn parse_iso_fields(s: &str, units: &[(char, i128)]) -> Option<i128> {
+    let mut total = 0i128;
+    let mut units = units.iter();
+    let mut rest = s;
+    while !rest.is_empty() {
+        let end = rest.find(|c: char| c.is_ascii_alphabetic())?;
+        let designator = rest.as_bytes()[end].to_ascii_uppercase() as char;
+        let (_, unit) = units.find(|(c, _)| *c == designator)?;
+        total = total.checked_add(scale_duration(&rest[..end], *unit)?)?;
+        rest = &rest[end + 1..];
+    }
+    Some(total)
+}
+
+/// Module to serialize and deserialize a **`Duration`**.
+///
+/// It's serialized as a humanized string, e.g. `1h 30m`, and deserialized from
+/// a humanized or ISO-8601 duration string, or seconds as a number.
+#[cfg(feature = "serde")]
+pub mod serde_x_duration {
+    use super::*;
+    use ::serde::{de, ser::Serializer};
+    use ::std::fmt;
+
+    struct DeDurationVisitor;
+
+    impl<'de> de::Visitor<'de> for DeDurationVisitor {
+        type Value = Duration;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "a duration string or seconds")
+        }
+
+        // from seconds
+        fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Ok(Duration::from_secs(value))
+        }
+
+        // from seconds
+        fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            u64::try_from(value)
+                .map(Duration::from_secs)
+                .map_err(|_| de::Error::invalid_value(de::Unexpected::Signed(value), &self))
+        }
+
+        // from seconds as float
+        fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Duration::try_from_secs_f64(value)
+                .map_err(|_| de::Error::invalid_value(de::Unexpected::Float(value), &self))
+        }
+
+        fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            duration_from_str(value)
+                .ok_or_else(|| de::Error::invalid_value(de::Unexpected::Str(value), &self))
+        }
+    }
+
+    /// Function to serializing a **`Duration`**
+    pub fn serialize<S>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&duration_into_str(*duration))
+    }
+
+    /// Function to deserializing a **`Duration`**
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(DeDurationVisitor)
+    }
+}
+
+#[cfg(feature = "serde")]
+pub use serde_x_duration::{deserialize as de_x_duration, serialize as ser_x_duration};
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Error of a duration which is out of the range of the target type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DurationRangeError;
+
+impl std::fmt::Display for DurationRangeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("duration out of range")
+    }
+}
+
+impl std::error::Error for DurationRangeError {}
+
+/// Extension for formatting, parsing, checked arithmetic and conversions of `DurationMicros`.
+///
+/// # Examples
+///
+/// ```
+/// use xelf::datetime::*;
+///
+/// let d = DurationMicros::micros_from_duration_str("1d 2h 3m 4s").unwrap();
+/// assert_eq!(d, 93_784_000_000);
+/// assert_eq!(d.micros_into_duration_str(), "1d 2h 3m 4s");
+/// assert_eq!(d.micros_into_iso_duration_str(), "P1DT2H3M4S");
+/// assert_eq!(DurationMicros::micros_from_duration_str("PT0.5S"), Some(500_000));
+/// assert!(i64::MAX.micros_checked_add(1).is_err());
+/// ```
+pub trait DurationMicrosXlf: Sized {
+    /// Parse a humanized or ISO-8601 duration, see [`duration_from_str`], or microseconds
+    /// as an integer like the value itself, rather than seconds as `duration_from_str` does.
+    /// A negative duration starts with `-`, nanoseconds are truncated,
+    /// and a unit-less fraction, e.g. `1.5`, is rejected.
+    fn micros_from_duration_str(s: impl AsRef<str>) -> Option<Self>;
+
+    /// Convert into a humanized string, e.g. `1d 2h 3m 4s 500ms`.
+    fn micros_into_duration_str(&self) -> String;
+
+    /// Convert into an ISO-8601 duration of days and time, e.g. `P1DT2H3M4.5S`.
+    fn micros_into_iso_duration_str(&self) -> String;
+
+    /// Add a duration, with an error on overflow.
+    fn micros_checked_add(&self, rhs: Self) -> Result<Self, DurationRangeError>;
+
+    /// Subtract a duration, with an error on overflow.
+    fn micros_checked_sub(&self, rhs: Self) -> Result<Self, DurationRangeError>;
+
+    /// Multiply by a factor, with an error on overflow.
+    fn micros_checked_mul(&self, factor: i64) -> Result<Self, DurationRangeError>;
+
+    /// Convert from a `Duration`, where nanoseconds are truncated.
+    fn micros_from_std_duration(duration: Duration) -> Result<Self, DurationRangeError>;
+
+    /// Convert into a `Duration`, with an error if it's negative.
+    fn micros_into_std_duration(&self) -> Result<Duration, DurationRangeError>;
+
+    /// Convert from a `TimeDelta`, where nanoseconds are truncated.
+    fn micros_from_time_delta(delta: TimeDelta) -> Result<Self, DurationRangeError>;
+
+    /// Convert into a `TimeDelta`.
+    fn micros_into_time_delta(&self) -> TimeDelta;
+}
+
+impl DurationMicrosXlf for DurationMicros {
+    fn micros_from_duration_str(s: impl AsRef<str>) -> Option<Self> {
+        let s = s.as_ref().trim();
+        if s.parse::<f64>().is_ok() {
+            return s.parse().ok();
+        }
+        Self::try_from(parse_duration_nanos(s)? / 1_000).ok()
+    }
+
+    #[inline]
+    fn micros_into_duration_str(&self) -> String {
+        format_duration_nanos(*self as i128 * 1_000)
+    }
+
+    #[inline]
+    fn micros_into_iso_duration_str(&self) -> String {
+        format_iso_duration_nanos(*self as i128 * 1_000)
+    }
+
+    #[inline]
+    fn micros_checked_add(&self, rhs: Self) -> Result<Self, DurationRangeError> {
+        self.checked_add(rhs).ok_or(DurationRangeError)
+    }
+
+    #[inline]
+    fn micros_checked_sub(&self, rhs: Self) -> Result<Self, DurationRangeError> {
+        self.checked_sub(rhs).ok_or(DurationRangeError)
+    }
+
+    #[inline]
+    fn micros_checked_mul(&self, factor: i64) -> Result<Self, DurationRangeError> {
+        self.checked_mul(factor).ok_or(DurationRangeError)
+    }
+
+    fn micros_from_std_duration(duration: Duration) -> Result<Self, DurationRangeError> {
+        Self::try_from(duration.as_micros()).map_err(|_| DurationRangeError)
+    }
+
+    fn micros_into_std_duration(&self) -> Result<Duration, DurationRangeError> {
+        u64::try_from(*self)
+            .map(Duration::from_micros)
+            .map_err(|_| DurationRangeError)
+    }
+
+    fn micros_from_time_delta(delta: TimeDelta) -> Result<Self, DurationRangeError> {
+        // `num_microseconds()` of a negative delta rounds towards zero as well.
+        delta.num_microseconds().ok_or(DurationRangeError)
+    }
+
+    #[inline]
+    fn micros_into_time_delta(&self) -> TimeDelta {
+        TimeDelta::microseconds(*self)
+    }
+}
+
+/// Module to serialize and deserialize a **`DurationMicros`**.
+///
+/// It's serialized as a humanized string, e.g. `1h 30m`, and deserialized from
+/// a humanized or ISO-8601 duration string, or microseconds as a number.
+#[cfg(feature = "serde")]
+pub mod serde_x_duration_micros {
+    use super::*;
+    use ::serde::{
+        de::{self, Unexpected},
+        ser::Serializer,
+    };
+    use ::std::fmt;
+
+    struct DeDurationMicrosVisitor;
+
+    impl<'de> de::Visitor<'de> for DeDurationMicrosVisitor {
+        type Value = DurationMicros;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "a duration string or microseconds")
+        }
+
+        // from microseconds
+        fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            DurationMicros::try_from(value)
+                .map_err(|_| de::Error::invalid_value(Unexpected::Unsigned(value), &self))
+        }
+
+        // from microseconds
+        fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Ok(value)
+        }
+
+        // from microseconds as float, without a fraction
+        fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            if value.fract() == 0.0 && value.abs() < i64::MAX as f64 {
+                Ok(value as i64)
+            } else {
+                Err(de::Error::invalid_value(Unexpected::Float(value), &self))
+            }
+        }
+
+        // from a duration string, or microseconds as the number does
+        fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            DurationMicros::micros_from_duration_str(value)
+                .ok_or_else(|| de::Error::invalid_value(Unexpected::Str(value), &self))
+        }
+    }
+
+    /// Function to serializing a **`DurationMicros`**
+    pub fn serialize<S>(micros: &DurationMicros, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&micros.micros_into_duration_str())
+    }
+
+    /// Function to deserializing a **`DurationMicros`**
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<DurationMicros, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(DeDurationMicrosVisitor)
+    }
+
+    /// Serialize as an ISO-8601 duration, e.g. `PT1H30M`.
+    pub mod iso {
+        use super::*;
+
+        /// Function to serializing a **`DurationMicros`**
+        pub fn serialize<S>(micros: &DurationMicros, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.serialize_str(&micros.micros_into_iso_duration_str())
+        }
+
+        /// Function to deserializing a **`DurationMicros`**
+        pub fn deserialize<'de, D>(deserializer: D) -> Result<DurationMicros, D::Error>
+        where
+            D: de::Deserializer<'de>,
+        {
+            super::deserialize(deserializer)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+pub use serde_x_duration_micros::{
+    deserialize as de_x_duration_micros, serialize as ser_x_duration_micros,
+};
+#[cfg(feature = "serde")]
+pub use serde_x_duration_micros::{
+    iso::deserialize as de_x_duration_micros_iso, iso::serialize as ser_x_duration_micros_iso,
+};
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_utc_default() {
+        use super::*;
+        use crate::prelude::*;
+
+        #[derive(Clone, Debug, SmartDefault, Serialize, Deserialize)]
+        struct Person {
+            #[serde(default)]
+            name: String,
+            year: i32,
+            optional: Option<i32>,
+            //#[serde(with = "serde_x_utc", default = "utc_default")]
+            #[serde(default = "utc_default")]
+            #[default(utc_default())]
+            birth_on: DateTime<Utc>,
+        }
+
+        let jsn = json!({
+            "year": 32,
+            "birth_on": utc_into_str(Utc::now()),
+        });
+        let a: Person = serde_json::from_value(jsn).unwrap();
+        println!("{:?}", &a);
+
+        let s = serde_json::to_string(&a).unwrap();
+        println!("{}", &s);
+
+        let utc = utc_from_str("2022-01-01T00:00:01.345677Z").unwrap();
+        assert_eq!(
+            UnixTimeMicros::micros_from_utc_str("2022-01-01T00:00:01.345677Z"),
+            Some(utc.timestamp_micros())
+        );
+        assert_eq!(
+            UnixTimeMicros::micros_from_utc_str(utc.timestamp_micros().to_string()),
+            Some(utc.timestamp_micros())
+        );
+    }
+
+    #[test]
+    fn test_duration_str() {
+        use super::*;
+
+        let cases = [
+            ("1500ms", 1_500_000_000),
+            ("2h", 7_200_000_000_000),
+            ("1d 2h 3m 4s", 93_784_000_000_000),
+            ("1 hour 30 mins", 5_400_000_000_000),
+            ("1.5s", 1_500_000_000),
+            ("1w", 604_800_000_000_000),
+            ("90", 90_000_000_000),
+            ("0.25", 250_000_000),
+            ("PT5M", 300_000_000_000),
+            ("P1DT2H3M4.5S", 93_784_500_000_000),
+            ("p1w", 604_800_000_000_000),
+            ("PT0.000001S", 1_000),
+            ("-1s 5ms", -1_005_000_000),
+        ];
+        for (s, nanos) in cases {
+            assert_eq!(parse_duration_nanos(s), Some(nanos), "{}", s);
+        }
+        for s in [
+            "", "P", "PT", "P1H", "PT1D", "PT1S2M", "1x", "h", "1..5s", "1h 2",
+        ] {
+            assert_eq!(parse_duration_nanos(s), None, "{}", s);
+        }
+
+        assert_eq!(format_duration_nanos(0), "0s");
+        assert_eq!(
+            format_duration_nanos(93_784_500_000_000),
+            "1d 2h 3m 4s 500ms"
+        );
+        assert_eq!(format_duration_nanos(-1_000_001), "-1ms 1ns");
+        let d = Duration::new(93_784, 5);
+        assert_eq!(duration_from_str(&duration_into_str(d)), Some(d));
+        assert_eq!(duration_from_str("-1s"), None);
+    }
+
+    #[test]
+    fn test_duration_micros() {
+        use super::*;
+
+        let cases = [
+            (0, "0s", "PT0S"),
+            (500_000, "500ms", "PT0.5S"),
+            (90_000_000, "1m 30s", "PT1M30S"),
+            (86_400_000_000, "1d", "P1D"),
+            (93_784_000_000, "1d 2h 3m 4s", "P1DT2H3M4S"),
+            (3_600_000_001, "1h 1us", "PT1H0.000001S"),
+            (-93_784_500_000, "-1d 2h 3m 4s 500ms", "-P1DT2H3M4.5S"),
+        ];
+        for (micros, human, iso) in cases {
+            assert_eq!(micros.micros_into_duration_str(), human);
+            assert_eq!(micros.micros_into_iso_duration_str(), iso);
+            assert_eq!(
+                DurationMicros::micros_from_duration_str(human),
+                Some(micros)
+            );
+            assert_eq!(DurationMicros::micros_from_duration_str(iso), Some(micros));
+        }
+        assert_eq!(DurationMicros::micros_from_duration_str("1500ns"), Some(1));
+        assert_eq!(DurationMicros::micros_from_duration_str(" -90 "), Some(-90));
+        assert_eq!(DurationMicros::micros_from_duration_str("1.5"), None);
+        assert_eq!(DurationMicros::micros_from_duration_str("1x"), None);
+        assert_eq!(
+            DurationMicros::micros_from_duration_str("999999999999999d"),
+            None
+        );
+
+        assert_eq!(1.micros_checked_add(2), Ok(3));
+        assert_eq!(i64::MAX.micros_checked_add(1), Err(DurationRangeError));
+        assert_eq!(i64::MIN.micros_checked_sub(1), Err(DurationRangeError));
+        assert_eq!(i64::MAX.micros_checked_mul(2), Err(DurationRangeError));
+        assert_eq!(DurationRangeError.to_string(), "duration out of range");
+
+        let d = Duration::new(93_784, 500_999);
+        assert_eq!(
+            DurationMicros::micros_from_std_duration(d),
+            Ok(93_784_000_500)
+        );
+        assert_eq!(
+            DurationMicros::micros_from_std_duration(Duration::MAX),
+            Err(DurationRangeError)
+        );
+        assert_eq!(
+            93_784_000_500.micros_into_std_duration(),
+            Ok(Duration::new(93_784, 500_000))
+        );
+        assert_eq!((-1).micros_into_std_duration(), Err(DurationRangeError));
+        assert_eq!(
+            DurationMicros::micros_from_time_delta(TimeDelta::nanoseconds(-1_999)),
+            Ok(-1)
+        );
+        assert_eq!(
+            DurationMicros::micros_from_time_delta(TimeDelta::MAX),
+            Err(DurationRangeError)
+        );
+        assert_eq!((-1).micros_into_time_delta(), TimeDelta::microseconds(-1));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_x_duration_micros() {
+        use super::*;
+        use crate::prelude::*;
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Job {
+            #[serde(with = "serde_x_duration_micros")]
+            timeout: DurationMicros,
+            #[serde(with = "serde_x_duration_micros::iso")]
+            interval: DurationMicros,
+        }
+
+        let a: Job =
+            serde_json::from_value(json!({"timeout": 1_500_000, "interval": "1h 30m"})).unwrap();
+        assert_eq!(
+            a,
+            Job {
+                timeout: 1_500_000,
+                interval: 5_400_000_000
+            }
+        );
+        let jsn = serde_json::to_value(&a).unwrap();
+        assert_eq!(jsn, json!({"timeout": "1s 500ms", "interval": "PT1H30M"}));
+        assert_eq!(serde_json::from_value::<Job>(jsn).unwrap(), a);
+        assert!(serde_json::from_value::<Job>(json!({"timeout": "x", "interval": 0})).is_err());
+
+        // Unit-less integers are microseconds, both as numbers and strings.
+        let b: Job = serde_json::from_value(json!({"timeout": 90, "interval": "90"})).unwrap();
+        assert_eq!(
+            b,
+            Job {
+                timeout: 90,
+                interval: 90
+            }
+        );
+        assert!(serde_json::from_value::<Job>(json!({"timeout": "1.5", "interval": 0})).is_err());
+        assert!(serde_json::from_value::<Job>(json!({"timeout": 1.5, "interval": 0})).is_err());
+        assert!(serde_json::from_value::<Job>(json!({"timeout": 2.0, "interval": 0})).is_ok());
+
+        // The serde module agrees with `micros_from_duration_str`.
+        for v in [
+            json!(90),
+            json!("90"),
+            json!("90s"),
+            json!("PT1.5S"),
+            json!("1.5"),
+        ] {
+            let s = v.as_str().map_or_else(|| v.to_string(), str::to_owned);
+            let job = serde_json::from_value::<Job>(json!({"timeout": v, "interval": 0}));
+            assert_eq!(
+                job.ok().map(|x| x.timeout),
+                DurationMicros::micros_from_duration_str(s)
+            );
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_x_utc() {
+        use super::*;
+        use crate::prelude::*;
+
+        #[serde_as]
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Event {
+            #[serde_as(as = "XUtc")]
+            start: DateTimeUtc,
+            #[serde_as(as = "XUtc")]
+            end: Option<DateTimeUtc>,
+            #[serde_as(as = "Vec<XUtc>")]
+            reminders: Vec<Option<DateTimeUtc>>,
+        }
+
+        let a: Event = serde_json::from_value(json!({
+            "start": "2022-01-01T00:00:01Z",
+            "end": "",
+            "reminders": [1_640_995_201_000_i64, null, ""],
+        }))
+        .unwrap();
+        let start = utc_from_str("2022-01-01T00:00:01Z").unwrap();
+        assert_eq!(a.start, start);
+        assert_eq!(a.end, None);
+        assert_eq!(a.reminders, [Some(start), None, None]);
+        let jsn = serde_json::to_value(&a).unwrap();
+        assert_eq!(jsn["start"], utc_into_str(start));
+        assert_eq!(serde_json::from_value::<Event>(jsn).unwrap(), a);
+    }
+}
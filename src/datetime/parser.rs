@@ -0,0 +1,517 @@
+//! Configurable parsing of timestamps in multiple formats.
+
+use super::{scale_duration, DateTimeUtc, UnixTimeMicros, NANOS_PER_SEC};
+use ::chrono::prelude::*;
+use ::std::borrow::Cow;
+
+#[cfg(feature = "tz")]
+use super::{tz_from_local, Tz};
+
+/// Unit of a Unix epoch timestamp.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EpochUnit {
+    Secs,
+    Millis,
+    Micros,
+    Nanos,
+}
+
+impl EpochUnit {
+    /// Detect the unit by the magnitude of a timestamp,
+    /// which is right for times between 1973-03-03 and 5138-11-16.
+    pub fn detect(value: i64) -> Self {
+        match value.unsigned_abs() {
+            v if v < 100_000_000_000 => Self::Secs,
+            v if v < 100_000_000_000_000 => Self::Millis,
+            v if v < 100_000_000_000_000_000 => Self::Micros,
+            _ => Self::Nanos,
+        }
+    }
+
+    /// Get the length of the unit in nanoseconds.
+    pub fn nanos(self) -> i64 {
+        match self {
+            Self::Secs => 1_000_000_000,
+            Self::Millis => 1_000_000,
+            Self::Micros => 1_000,
+            Self::Nanos => 1,
+        }
+    }
+}
+
+/// A format tried by [`UtcParser`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UtcFormat {
+    /// RFC 3339, e.g. `2024-03-10T08:00:00.5+08:00`.
+    Rfc3339,
+    /// RFC 2822, e.g. `Sun, 10 Mar 2024 08:00:00 +0800`.
+    Rfc2822,
+    /// A `chrono` format with an offset, e.g. `%d/%m/%Y %H:%M %z`.
+    Offset(Cow<'static, str>),
+    /// A `chrono` format of a date and time without an offset, in the default zone,
+    /// e.g. `%Y-%m-%d %H:%M:%S`.
+    Naive(Cow<'static, str>),
+    /// A `chrono` format of a date for the start of the day in the default zone,
+    /// e.g. `%Y-%m-%d`.
+    Date(Cow<'static, str>),
+    /// A Unix epoch timestamp as an integer or a decimal number, the unit is detected
+    /// by [`EpochUnit::detect`] if it's `None`.
+    Epoch(Option<EpochUnit>),
+}
+
+#[derive(Clone, Debug)]
+enum LocalZone {
+    Fixed(FixedOffset),
+    #[cfg(feature = "tz")]
+    Tz(Tz),
+}
+
+impl LocalZone {
+    fn to_utc(&self, local: NaiveDateTime) -> Option<DateTimeUtc> {
+        match self {
+            Self::Fixed(offset) => offset
+                .from_local_datetime(&local)
+                .single()
+                .map(|x| x.with_timezone(&Utc)),
+            #[cfg(feature = "tz")]
+            Self::Tz(tz) => Some(tz_from_local(*tz, local).with_timezone(&Utc)),
+        }
+    }
+}
+
+/// Parser of timestamps which tries a list of formats in order.
+///
+/// The default parser accepts RFC 3339, RFC 2822, `YYYY-MM-DD HH:MM:SS[.f]`,
+/// `YYYY-MM-DDTHH:MM:SS[.f]`, `YYYY-MM-DD` and epoch timestamps of any unit,
+/// where times without an offset are in UTC.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::FixedOffset;
+/// use xelf::datetime::*;
+///
+/// let parser = UtcParser::default().offset(FixedOffset::east_opt(8 * 3600).unwrap());
+/// let utc = utc_from_str("2024-03-10T00:00:00Z").unwrap();
+/// assert_eq!(parser.parse("2024-03-10 08:00:00"), Some(utc));
+/// assert_eq!(parser.parse("Sun, 10 Mar 2024 00:00:00 GMT"), Some(utc));
+/// assert_eq!(parser.parse("1710028800"), Some(utc));
+/// assert_eq!(parser.parse("1710028800000"), Some(utc));
+///
+/// let parser = UtcParser::new([UtcFormat::Naive("%d/%m/%Y %H:%M".into())]);
+/// assert_eq!(parser.parse("10/03/2024 00:00"), Some(utc));
+/// assert_eq!(parser.parse("1710028800"), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct UtcParser {
+    formats: Vec<UtcFormat>,
+    zone: LocalZone,
+}
+
+impl Default for UtcParser {
+    fn default() -> Self {
+        Self::new([
+            UtcFormat::Rfc3339,
+            UtcFormat::Rfc2822,
+            UtcFormat::Naive("%Y-%m-%d %H:%M:%S%.f".into()),
+            UtcFormat::Naive("%Y-%m-%dT%H:%M:%S%.f".into()),
+            UtcFormat::Date("%Y-%m-%d".into()),
+            UtcFormat::Epoch(None),
+        ])
+    }
+}
+
+impl UtcParser {
+    /// Create a parser with formats to try in order, times without an offset are in UTC.
+    pub fn new(formats: impl IntoIterator<Item = UtcFormat>) -> Self {
+        Self {
+            formats: formats.into_iter().collect(),
+            zone: LocalZone::Fixed(FixedOffset::east_opt(0).unwrap()),
+        }
+    }
+
+    /// Append a format to try after the others.
+    pub fn format(mut self, format: UtcFormat) -> Self {
+        self.formats.push(format);
+        self
+    }
+
+    /// Set the offset of times without an offset.
+    pub fn offset(mut self, offset: FixedOffset) -> Self {
+        self.zone = LocalZone::Fixed(offset);
+        self
+    }
+
+    /// Set the zone of times without an offset, see [`tz_from_local`] for times
+    /// which do not exist or are ambiguous.
+    #[cfg(feature = "tz")]
+    pub fn tz(mut self, tz: Tz) -> Self {
+        self.zone = LocalZone::Tz(tz);
+        self
+    }
+
+    /// Get the formats to try.
+    pub fn formats(&self) -> &[UtcFormat] {
+        &self.formats
+    }
+
+    /// Parse a string by the first format which matches.
+    pub fn parse(&self, s: &str) -> Option<DateTimeUtc> {
+        let s = s.trim();
+        self.formats.iter().find_map(|format| match format {
+            UtcFormat::Rfc3339 => DateTime::parse_from_rfc3339(s)
+                .ok()
+                .map(|x| x.with_timezone(&Utc)),
+            UtcFormat::Rfc2822 => DateTime::parse_from_rfc2822(s)
+                .ok()
+                .map(|x| x.with_timezone(&Utc)),
+            UtcFormat::Offset(f) => DateTime::parse_from_str(s, f)
+                .ok()
+                .map(|x| x.with_timezone(&Utc)),
+            UtcFormat::Naive(f) => NaiveDateTime::parse_from_str(s, f)
+                .ok()
+                .and_then(|x| self.zone.to_utc(x)),
+            UtcFormat::Date(f) => NaiveDate::parse_from_str(s, f)
+                .ok()
+                .and_then(|x| self.zone.to_utc(x.and_time(NaiveTime::MIN))),
+            UtcFormat::Epoch(unit) => epoch_from_str(s, *unit),
+        })
+    }
+
+    /// Parse a string into a Unix timestamp in microseconds.
+    #[inline]
+    pub fn parse_micros(&self, s: &str) -> Option<UnixTimeMicros> {
+        self.parse(s).map(|x| x.timestamp_micros())
+    }
+
+    /// Convert an epoch timestamp by the first [`UtcFormat::Epoch`] format,
+    /// or `None` if there is no such format.
+    pub fn parse_epoch(&self, value: i64) -> Option<DateTimeUtc> {
+        let unit = self
+            .epoch_unit()?
+            .unwrap_or_else(|| EpochUnit::detect(value));
+        utc_from_nanos(value as i128 * unit.nanos() as i128)
+    }
+
+    /// Convert an epoch timestamp as a float by the first [`UtcFormat::Epoch`] format,
+    /// or `None` if there is no such format.
+    pub fn parse_epoch_f64(&self, value: f64) -> Option<DateTimeUtc> {
+        if !value.is_finite() {
+            return None;
+        }
+        let unit = self
+            .epoch_unit()?
+            .unwrap_or_else(|| EpochUnit::detect(value as i64));
+        utc_from_nanos((value * unit.nanos() as f64).round() as i128)
+    }
+
+    fn epoch_unit(&self) -> Option<Option<EpochUnit>> {
+        self.formats.iter().find_map(|x| match x {
+            UtcFormat::Epoch(unit) => Some(*unit),
+            _ => None,
+        })
+    }
+}
+
+fn epoch_from_str(s: &str, unit: Option<EpochUnit>) -> Option<DateTimeUtc> {
+    let (negative, digits) = match s.strip_prefix('-') {
+        Some(v) => (true, v),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+    let unit = match unit {
+        Some(v) => v,
+        None => {
+            let int = digits.split('.').next().unwrap_or_default();
+            EpochUnit::detect(if int.is_empty() { 0 } else { int.parse().ok()? })
+        }
+    };
+    let nanos = scale_duration(digits, unit.nanos() as i128)?;
+    utc_from_nanos(if negative { -nanos } else { nanos })
+}
+
+fn utc_from_nanos(nanos: i128) -> Option<DateTimeUtc> {
+    let secs = i64::try_from(nanos.div_euclid(NANOS_PER_SEC)).ok()?;
+    Utc.timestamp_opt(secs, nanos.rem_euclid(NANOS_PER_SEC) as u32)
+        .single()
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Parser chosen by a type, for [`XUtcParsed`] and `#[serde(with)]` modules.
+#[cfg(feature = "serde")]
+pub trait UtcParserSpec {
+    /// Get the parser.
+    fn parser() -> &'static UtcParser;
+}
+
+/// The default [`UtcParser`].
+#[cfg(feature = "serde")]
+pub struct DefaultUtcParser;
+
+#[cfg(feature = "serde")]
+impl UtcParserSpec for DefaultUtcParser {
+    fn parser() -> &'static UtcParser {
+        static PARSER: std::sync::OnceLock<UtcParser> = std::sync::OnceLock::new();
+        PARSER.get_or_init(UtcParser::default)
+    }
+}
+
+#[cfg(feature = "serde")]
+pub(super) struct DeUtcParserVisitor<'a>(pub(super) &'a UtcParser);
+
+#[cfg(feature = "serde")]
+impl<'de> ::serde::de::Visitor<'de> for DeUtcParserVisitor<'_> {
+    type Value = DateTimeUtc;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "a date and time string or an epoch timestamp")
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+    where
+        E: ::serde::de::Error,
+    {
+        i64::try_from(value)
+            .ok()
+            .and_then(|x| self.0.parse_epoch(x))
+            .ok_or_else(|| E::invalid_value(::serde::de::Unexpected::Unsigned(value), &self))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+    where
+        E: ::serde::de::Error,
+    {
+        self.0
+            .parse_epoch(value)
+            .ok_or_else(|| E::invalid_value(::serde::de::Unexpected::Signed(value), &self))
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E>
+    where
+        E: ::serde::de::Error,
+    {
+        self.0
+            .parse_epoch_f64(value)
+            .ok_or_else(|| E::invalid_value(::serde::de::Unexpected::Float(value), &self))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: ::serde::de::Error,
+    {
+        self.0
+            .parse(value)
+            .ok_or_else(|| E::invalid_value(::serde::de::Unexpected::Str(value), &self))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl UtcParser {
+    /// Deserialize a **`DateTimeUtc`** from a string or an epoch timestamp,
+    /// to implement a function for `#[serde(deserialize_with)]`.
+    pub fn deserialize<'de, D>(&self, deserializer: D) -> Result<DateTimeUtc, D::Error>
+    where
+        D: ::serde::de::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(DeUtcParserVisitor(self))
+    }
+}
+
+/// Adapter for `#[serde_as]` to deserialize a **`DateTimeUtc`** by a chosen [`UtcParser`],
+/// and serialize it like [`serde_x_utc`](super::serde_x_utc).
+///
+/// It also applies to `Option<DateTimeUtc>`, where null and an empty string are `None`.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::FixedOffset;
+/// use serde::Deserialize;
+/// use serde_with::serde_as;
+/// use std::sync::OnceLock;
+/// use xelf::datetime::*;
+///
+/// struct Beijing;
+///
+/// impl UtcParserSpec for Beijing {
+///     fn parser() -> &'static UtcParser {
+///         static PARSER: OnceLock<UtcParser> = OnceLock::new();
+///         PARSER.get_or_init(|| {
+///             UtcParser::default().offset(FixedOffset::east_opt(8 * 3600).unwrap())
+///         })
+///     }
+/// }
+///
+/// #[serde_as]
+/// #[derive(Deserialize)]
+/// struct Order {
+///     #[serde_as(as = "XUtcParsed<Beijing>")]
+///     created: DateTimeUtc,
+/// }
+///
+/// let order: Order = serde_json::from_str(r#"{"created": "2024-03-10 08:00:00"}"#).unwrap();
+/// assert_eq!(order.created, utc_from_str("2024-03-10T00:00:00Z").unwrap());
+/// ```
+#[cfg(feature = "serde_with")]
+pub struct XUtcParsed<P = DefaultUtcParser>(std::marker::PhantomData<P>);
+
+#[cfg(feature = "serde_with")]
+impl<'de, P: UtcParserSpec> serde_with::DeserializeAs<'de, DateTimeUtc> for XUtcParsed<P> {
+    fn deserialize_as<D>(deserializer: D) -> Result<DateTimeUtc, D::Error>
+    where
+        D: ::serde::Deserializer<'de>,
+    {
+        P::parser().deserialize(deserializer)
+    }
+}
+
+#[cfg(feature = "serde_with")]
+impl<'de, P: UtcParserSpec> serde_with::DeserializeAs<'de, Option<DateTimeUtc>> for XUtcParsed<P> {
+    fn deserialize_as<D>(deserializer: D) -> Result<Option<DateTimeUtc>, D::Error>
+    where
+        D: ::serde::Deserializer<'de>,
+    {
+        use crate::serde::DeOptionVisitor;
+        deserializer.deserialize_any(DeOptionVisitor(DeUtcParserVisitor(P::parser())))
+    }
+}
+
+#[cfg(feature = "serde_with")]
+impl<P> serde_with::SerializeAs<DateTimeUtc> for XUtcParsed<P> {
+    fn serialize_as<S>(utc: &DateTimeUtc, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        super::serde_x_utc::serialize(utc, serializer)
+    }
+}
+
+#[cfg(feature = "serde_with")]
+impl<P> serde_with::SerializeAs<Option<DateTimeUtc>> for XUtcParsed<P> {
+    fn serialize_as<S>(utc: &Option<DateTimeUtc>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ::serde::Serializer,
+    {
+        match utc {
+            Some(v) => super::serde_x_utc::serialize(v, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::datetime::*;
+    use chrono::FixedOffset;
+
+    fn utc(s: &str) -> DateTimeUtc {
+        utc_from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_utc_parser() {
+        assert_eq!(EpochUnit::detect(1_710_028_800), EpochUnit::Secs);
+        assert_eq!(EpochUnit::detect(-1_710_028_800_000), EpochUnit::Millis);
+        assert_eq!(EpochUnit::detect(1_710_028_800_000_000), EpochUnit::Micros);
+        assert_eq!(EpochUnit::detect(i64::MAX), EpochUnit::Nanos);
+
+        let t = utc("2024-03-10T00:00:00Z");
+        let parser = UtcParser::default();
+        let cases = [
+            ("2024-03-10T08:00:00+08:00", t),
+            ("Sun, 10 Mar 2024 00:00:00 +0000", t),
+            ("  2024-03-10 00:00:00  ", t),
+            ("2024-03-10T00:00:00.25", utc("2024-03-10T00:00:00.25Z")),
+            ("2024-03-10", t),
+            ("1710028800", t),
+            ("1710028800.5", utc("2024-03-10T00:00:00.5Z")),
+            ("1710028800500", utc("2024-03-10T00:00:00.5Z")),
+            ("1710028800000001", utc("2024-03-10T00:00:00.000001Z")),
+            ("1710028800000000001", utc("2024-03-10T00:00:00.000000001Z")),
+            ("-1.5", utc("1969-12-31T23:59:58.5Z")),
+        ];
+        for (s, v) in cases {
+            assert_eq!(parser.parse(s), Some(v), "{}", s);
+        }
+        for s in ["", "2024-13-01", "10/03/2024", "1e9", "-", "."] {
+            assert_eq!(parser.parse(s), None, "{}", s);
+        }
+        assert_eq!(
+            parser.parse_micros("1710028800"),
+            Some(t.timestamp_micros())
+        );
+        assert_eq!(parser.parse_epoch(1_710_028_800_000), Some(t));
+        assert_eq!(
+            parser.parse_epoch_f64(1_710_028_800.5),
+            parser.parse("1710028800.5")
+        );
+        assert_eq!(parser.parse_epoch_f64(f64::NAN), None);
+
+        // Formats are tried in order, with a fixed epoch unit and a default offset.
+        let parser = UtcParser::new([
+            UtcFormat::Date("%d/%m/%Y".into()),
+            UtcFormat::Offset("%d/%m/%Y %H:%M %z".into()),
+            UtcFormat::Epoch(Some(EpochUnit::Millis)),
+        ])
+        .offset(FixedOffset::west_opt(5 * 3600).unwrap());
+        assert_eq!(parser.formats().len(), 3);
+        assert_eq!(
+            parser.parse("09/03/2024"),
+            Some(utc("2024-03-09T05:00:00Z"))
+        );
+        assert_eq!(parser.parse("10/03/2024 08:00 +0800"), Some(t));
+        assert_eq!(
+            parser.parse("1710028800"),
+            Some(utc("1970-01-20T19:00:28.8Z"))
+        );
+        assert_eq!(parser.parse("2024-03-10"), None);
+        assert_eq!(UtcParser::new([]).parse_epoch(0), None);
+
+        #[cfg(feature = "tz")]
+        {
+            let parser = UtcParser::default().tz(tz_from_name("America/New_York").unwrap());
+            assert_eq!(
+                parser.parse("2024-03-10 02:30"),
+                None,
+                "no format without seconds"
+            );
+            let parser = parser.format(UtcFormat::Naive("%Y-%m-%d %H:%M".into()));
+            assert_eq!(
+                parser.parse("2024-03-10 02:30"),
+                Some(utc("2024-03-10T07:30:00Z"))
+            );
+            assert_eq!(
+                parser.parse("2024-07-01"),
+                Some(utc("2024-07-01T04:00:00Z"))
+            );
+        }
+    }
+
+    #[cfg(feature = "serde_with")]
+    #[test]
+    fn test_x_utc_parsed() {
+        use crate::prelude::*;
+
+        #[serde_as]
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Feed {
+            #[serde(with = "serde_x_utc::parsed")]
+            at: DateTimeUtc,
+            #[serde_as(as = "Vec<XUtcParsed>")]
+            times: Vec<Option<DateTimeUtc>>,
+        }
+
+        let t = utc("2024-03-10T00:00:00Z");
+        let a: Feed = serde_json::from_value(json!({
+            "at": "Sun, 10 Mar 2024 00:00:00 GMT",
+            "times": [1_710_028_800, 1_710_028_800_000_i64, 1_710_028_800.0, "2024-03-10", null, ""],
+        }))
+        .unwrap();
+        assert_eq!(a.at, t);
+        assert_eq!(a.times, [Some(t), Some(t), Some(t), Some(t), None, None]);
+        let jsn = serde_json::to_value(&a).unwrap();
+        assert_eq!(jsn["at"], "2024-03-10T00:00:00.000000Z");
+        assert_eq!(serde_json::from_value::<Feed>(jsn).unwrap(), a);
+        assert!(serde_json::from_value::<Feed>(json!({"at": "soon", "times": []})).is_err());
+    }
+}
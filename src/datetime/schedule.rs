@@ -0,0 +1,858 @@
+//! Cron and interval schedules.
+
+use super::{
+    zoned_from_local, DateTimeUtc, DurationMicros, DurationMicrosXlf, UnixTimeMicros,
+    UnixTimestampXlf,
+};
+use ::chrono::{prelude::*, TimeDelta};
+use ::std::{fmt, str::FromStr};
+
+/// Days to search for the next or the previous time of a cron expression,
+/// which covers the 28-year cycle of the Gregorian calendar.
+const MAX_SEARCH_DAYS: u32 = 28 * 366;
+
+/// Margin around an instant to sample the offsets of a time zone,
+/// which is longer than any offset change, so that times in a gap are not missed.
+const OFFSET_MARGIN_HOURS: i64 = 3;
+
+/// Error of parsing a schedule.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScheduleError {
+    /// The number of fields of a cron expression is not 5 or 6.
+    FieldCount(usize),
+    /// A field of a cron expression is invalid.
+    InvalidField { field: &'static str, value: String },
+    /// The interval is not a positive duration.
+    InvalidInterval(String),
+}
+
+impl fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FieldCount(n) => write!(f, "expected 5 or 6 cron fields, got {}", n),
+            Self::InvalidField { field, value } => {
+                write!(f, "invalid cron {} field `{}`", field, value)
+            }
+            Self::InvalidInterval(s) => write!(f, "invalid interval `{}`", s),
+        }
+    }
+}
+
+impl std::error::Error for ScheduleError {}
+
+////////////////////////////////////////////////////////////////////////////////
+
+struct FieldSpec {
+    name: &'static str,
+    min: u32,
+    max: u32,
+    names: &'static [&'static str],
+}
+
+const SECOND: FieldSpec = FieldSpec {
+    name: "second",
+    min: 0,
+    max: 59,
+    names: &[],
+};
+const MINUTE: FieldSpec = FieldSpec {
+    name: "minute",
+    min: 0,
+    max: 59,
+    names: &[],
+};
+const HOUR: FieldSpec = FieldSpec {
+    name: "hour",
+    min: 0,
+    max: 23,
+    names: &[],
+};
+const DAY: FieldSpec = FieldSpec {
+    name: "day",
+    min: 1,
+    max: 31,
+    names: &[],
+};
+const MONTH: FieldSpec = FieldSpec {
+    name: "month",
+    min: 1,
+    max: 12,
+    names: &[
+        "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+    ],
+};
+// 7 is also Sunday.
+const WEEKDAY: FieldSpec = FieldSpec {
+    name: "weekday",
+    min: 0,
+    max: 7,
+    names: &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"],
+};
+
+impl FieldSpec {
+    /// Parse a field into a bit mask, and whether it starts with `*` or `?`.
+    fn parse(&self, s: &str) -> Result<(u64, bool), ScheduleError> {
+        let error = || ScheduleError::InvalidField {
+            field: self.name,
+            value: s.to_owned(),
+        };
+        let mut mask = 0u64;
+        for part in s.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => (range, Some(step.parse::<u32>().map_err(|_| error())?)),
+                None => (part, None),
+            };
+            let (lo, hi) = if range == "*" || range == "?" {
+                (self.min, self.max)
+            } else if let Some((lo, hi)) = range.split_once('-') {
+                (
+                    self.value(lo).ok_or_else(error)?,
+                    self.value(hi).ok_or_else(error)?,
+                )
+            } else {
+                let v = self.value(range).ok_or_else(error)?;
+                (v, if step.is_some() { self.max } else { v })
+            };
+            if lo > hi || step == Some(0) {
+                return Err(error());
+            }
+            for v in (lo..=hi).step_by(step.unwrap_or(1) as usize) {
+                mask |= 1 << v;
+            }
+        }
+        if self.name == WEEKDAY.name && mask & (1 << 7) != 0 {
+            mask = (mask | 1) & !(1 << 7);
+        }
+        Ok((mask, s.starts_with(['*', '?'])))
+    }
+
+    fn value(&self, s: &str) -> Option<u32> {
+        let v = match self.names.iter().position(|x| x.eq_ignore_ascii_case(s)) {
+            Some(i) => i as u32 + self.min,
+            None => s.parse().ok()?,
+        };
+        (self.min..=self.max).contains(&v).then_some(v)
+    }
+}
+
+#[inline]
+fn has(mask: u64, v: u32) -> bool {
+    mask & (1 << v) != 0
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Cron {
+    seconds: u64,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Whether a date matches both the day and the weekday, or either of them.
+    match_both: bool,
+}
+
+impl Cron {
+    fn parse(expr: &str) -> Result<Self, ScheduleError> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let fields = match fields.len() {
+            5 => [&["0"][..], &fields[..]].concat(),
+            6 => fields,
+            n => return Err(ScheduleError::FieldCount(n)),
+        };
+        let (days, any_day) = DAY.parse(fields[3])?;
+        let (weekdays, any_weekday) = WEEKDAY.parse(fields[5])?;
+        Ok(Self {
+            seconds: SECOND.parse(fields[0])?.0,
+            minutes: MINUTE.parse(fields[1])?.0,
+            hours: HOUR.parse(fields[2])?.0,
+            days,
+            months: MONTH.parse(fields[4])?.0,
+            weekdays,
+            match_both: any_day || any_weekday,
+        })
+    }
+
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        if !has(self.months, date.month()) {
+            return false;
+        }
+        let day = has(self.days, date.day());
+        let weekday = has(self.weekdays, date.weekday().num_days_from_sunday());
+        if self.match_both {
+            day && weekday
+        } else {
+            day || weekday
+        }
+    }
+
+    fn time_at_or_after(&self, t: NaiveTime) -> Option<NaiveTime> {
+        let (h0, m0, s0) = (t.hour(), t.minute(), t.second());
+        for h in (h0..24).filter(|&h| has(self.hours, h)) {
+            let m_from = if h == h0 { m0 } else { 0 };
+            for m in (m_from..60).filter(|&m| has(self.minutes, m)) {
+                let s_from = if h == h0 && m == m0 { s0 } else { 0 };
+                if let Some(s) = (s_from..60).find(|&s| has(self.seconds, s)) {
+                    return NaiveTime::from_hms_opt(h, m, s);
+                }
+            }
+        }
+        None
+    }
+
+    fn time_at_or_before(&self, t: NaiveTime) -> Option<NaiveTime> {
+        let (h0, m0, s0) = (t.hour(), t.minute(), t.second());
+        for h in (0..=h0).rev().filter(|&h| has(self.hours, h)) {
+            let m_to = if h == h0 { m0 } else { 59 };
+            for m in (0..=m_to).rev().filter(|&m| has(self.minutes, m)) {
+                let s_to = if h == h0 && m == m0 { s0 } else { 59 };
+                if let Some(s) = (0..=s_to).rev().find(|&s| has(self.seconds, s)) {
+                    return NaiveTime::from_hms_opt(h, m, s);
+                }
+            }
+        }
+        None
+    }
+
+    /// Get the first local time which matches at or after `from`.
+    fn next_local(&self, from: NaiveDateTime) -> Option<NaiveDateTime> {
+        let (mut date, mut time) = (from.date(), from.time());
+        for _ in 0..MAX_SEARCH_DAYS {
+            if self.matches_date(date) {
+                if let Some(t) = self.time_at_or_after(time) {
+                    return Some(date.and_time(t));
+                }
+            }
+            date = date.succ_opt()?;
+            time = NaiveTime::MIN;
+        }
+        None
+    }
+
+    /// Get the last local time which matches at or before `from`.
+    fn prev_local(&self, from: NaiveDateTime) -> Option<NaiveDateTime> {
+        let (mut date, mut time) = (from.date(), from.time());
+        for _ in 0..MAX_SEARCH_DAYS {
+            if self.matches_date(date) {
+                if let Some(t) = self.time_at_or_before(time) {
+                    return Some(date.and_time(t));
+                }
+            }
+            date = date.pred_opt()?;
+            time = NaiveTime::from_hms_opt(23, 59, 59)?;
+        }
+        None
+    }
+
+    /// Get the first instant after `after` in the zone `tz`.
+    ///
+    /// The local times map to instants by the offset before a change up to the later one
+    /// of the repeated hour or the gap, and by the offset after it since then.
+    /// A run of matches in one offset is skipped at once, so it takes a few steps.
+    fn next_after<Z: TimeZone>(&self, after: DateTimeUtc, tz: &Z) -> Option<DateTimeUtc> {
+        let offset = offsets_around(tz, after).min()?;
+        let mut from =
+            floor_seconds(after.naive_utc().checked_add_signed(offset)?)? + TimeDelta::seconds(1);
+        loop {
+            let local = self.next_local(from)?;
+            let (t, offset) = resolve_local(tz, local);
+            if t > after {
+                return Some(t);
+            }
+            // The first local time after `after` in the same offset.
+            let skip = floor_seconds(after.naive_utc().checked_add_signed(offset)?)?
+                + TimeDelta::seconds(1);
+            from = if resolve_local(tz, skip).1 == offset {
+                skip
+            } else {
+                // The matches before the change are all before `after`.
+                let (mut lo, mut hi) = (local, skip);
+                while hi - lo > TimeDelta::seconds(1) {
+                    let mid = lo + TimeDelta::seconds((hi - lo).num_seconds() / 2);
+                    if resolve_local(tz, mid).1 == offset {
+                        lo = mid;
+                    } else {
+                        hi = mid;
+                    }
+                }
+                hi
+            };
+        }
+    }
+
+    /// Get the last instant before `before` in the zone `tz`, see [`Self::next_after`].
+    fn prev_before<Z: TimeZone>(&self, before: DateTimeUtc, tz: &Z) -> Option<DateTimeUtc> {
+        let last_before = |offset: TimeDelta| {
+            let local = before.naive_utc().checked_add_signed(offset)?;
+            let floor = floor_seconds(local)?;
+            Some(if floor == local {
+                floor - TimeDelta::seconds(1)
+            } else {
+                floor
+            })
+        };
+        let mut from = last_before(offsets_around(tz, before).max()?)?;
+        loop {
+            let local = self.prev_local(from)?;
+            let (t, offset) = resolve_local(tz, local);
+            if t < before {
+                return Some(t);
+            }
+            // The last local time before `before` in the same offset.
+            let skip = last_before(offset)?;
+            from = if resolve_local(tz, skip).1 == offset {
+                skip
+            } else {
+                // The matches after the change are all at or after `before`.
+                let (mut lo, mut hi) = (skip, local);
+                while hi - lo > TimeDelta::seconds(1) {
+                    let mid = lo + TimeDelta::seconds((hi - lo).num_seconds() / 2);
+                    if resolve_local(tz, mid).1 == offset {
+                        hi = mid;
+                    } else {
+                        lo = mid;
+                    }
+                }
+                lo
+            };
+        }
+    }
+}
+
+/// Get the offsets of a zone around an instant, as `local - utc`.
+fn offsets_around<Z: TimeZone>(tz: &Z, t: DateTimeUtc) -> impl Iterator<Item = TimeDelta> + '_ {
+    let margin = TimeDelta::hours(OFFSET_MARGIN_HOURS);
+    [
+        t.checked_sub_signed(margin),
+        Some(t),
+        t.checked_add_signed(margin),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|x| {
+        let offset = tz.offset_from_utc_datetime(&x.naive_utc()).fix();
+        TimeDelta::seconds(offset.local_minus_utc() as i64)
+    })
+}
+
+/// Get the instant of a local time, and the offset it's mapped by as `local - utc`.
+fn resolve_local<Z: TimeZone>(tz: &Z, local: NaiveDateTime) -> (DateTimeUtc, TimeDelta) {
+    let t = zoned_from_local(tz, local).with_timezone(&Utc);
+    (t, local - t.naive_utc())
+}
+
+#[inline]
+fn floor_seconds(t: NaiveDateTime) -> Option<NaiveDateTime> {
+    t.with_nanosecond(0)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum ScheduleKind {
+    Cron(Cron),
+    Interval {
+        period: DurationMicros,
+        anchor: UnixTimeMicros,
+    },
+}
+
+/// Schedule of a cron expression or a fixed interval, with an optional jitter.
+///
+/// A cron expression has 5 fields of minute, hour, day, month and weekday,
+/// or 6 fields with the second first. A field is `*`, `?`, a value, a range `a-b`,
+/// a step `*/n`, `a/n` or `a-b/n`, or a list of them separated by `,`.
+/// Months and weekdays can be names like `JAN` and `MON`, and Sunday is `0` or `7`.
+/// As in Vixie cron, a date matches either the day or the weekday if both are restricted.
+/// `@yearly`, `@annually`, `@monthly`, `@weekly`, `@daily`, `@midnight` and `@hourly`
+/// are supported, and `@every 1h30m` is an interval, see [`duration_from_str`](super::duration_from_str).
+///
+/// Cron times are local in a zone: a time in the repeated hour at the end of DST fires once
+/// at the earlier instant, and a time in the gap at the start of DST fires after the gap.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::FixedOffset;
+/// use xelf::datetime::*;
+///
+/// let schedule: Schedule = "0 */15 9-17 * * MON-FRI".parse().unwrap();
+/// let tz = FixedOffset::east_opt(8 * 3600).unwrap();
+/// // 2024-03-08 17:50 on Friday in +08:00.
+/// let t = utc_from_str("2024-03-08T09:50:00Z").unwrap();
+/// assert_eq!(
+///     schedule.next_after(t, &tz),
+///     utc_from_str("2024-03-11T01:00:00Z").ok()
+/// );
+/// assert_eq!(
+///     schedule.prev_before(t, &tz),
+///     utc_from_str("2024-03-08T09:45:00Z").ok()
+/// );
+///
+/// let schedule: Schedule = "@every 90s".parse().unwrap();
+/// assert_eq!(
+///     schedule.next_after(t, &tz),
+///     utc_from_str("2024-03-08T09:51:00Z").ok()
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Schedule {
+    kind: ScheduleKind,
+    jitter: DurationMicros,
+}
+
+impl FromStr for Schedule {
+    type Err = ScheduleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let Some(name) = s.strip_prefix('@') else {
+            return Self::cron(s);
+        };
+        let name = name.to_ascii_lowercase();
+        if let Some(v) = name.strip_prefix("every") {
+            return DurationMicros::micros_from_duration_str(v)
+                .ok_or_else(|| ScheduleError::InvalidInterval(v.trim().to_owned()))
+                .and_then(Self::interval);
+        }
+        Self::cron(match name.as_str() {
+            "yearly" | "annually" => "0 0 1 1 *",
+            "monthly" => "0 0 1 * *",
+            "weekly" => "0 0 * * 0",
+            "daily" | "midnight" => "0 0 * * *",
+            "hourly" => "0 * * * *",
+            _ => {
+                return Err(ScheduleError::InvalidField {
+                    field: "macro",
+                    value: s.to_owned(),
+                })
+            }
+        })
+    }
+}
+
+impl Schedule {
+    /// Create a schedule of a cron expression of 5 or 6 fields.
+    pub fn cron(expr: &str) -> Result<Self, ScheduleError> {
+        Ok(Self {
+            kind: ScheduleKind::Cron(Cron::parse(expr)?),
+            jitter: 0,
+        })
+    }
+
+    /// Create a schedule of a fixed interval, which fires at the multiples of `period`
+    /// since the Unix epoch by default.
+    pub fn interval(period: DurationMicros) -> Result<Self, ScheduleError> {
+        if period <= 0 {
+            return Err(ScheduleError::InvalidInterval(
+                period.micros_into_duration_str(),
+            ));
+        }
+        Ok(Self {
+            kind: ScheduleKind::Interval { period, anchor: 0 },
+            jitter: 0,
+        })
+    }
+
+    /// Set the time which an interval is aligned to, it's ignored by a cron expression.
+    pub fn anchor(mut self, time: UnixTimeMicros) -> Self {
+        if let ScheduleKind::Interval { anchor, .. } = &mut self.kind {
+            *anchor = time;
+        }
+        self
+    }
+
+    /// Set the maximum random delay of a tick, see [`Self::jittered`].
+    pub fn jitter(mut self, jitter: DurationMicros) -> Self {
+        self.jitter = jitter.max(0);
+        self
+    }
+
+    /// Get the maximum random delay of a tick.
+    #[inline]
+    pub fn jitter_micros(&self) -> DurationMicros {
+        self.jitter
+    }
+
+    /// Get the first time of the schedule after `after`, in the zone `tz`.
+    pub fn next_after<Z: TimeZone>(&self, after: DateTimeUtc, tz: &Z) -> Option<DateTimeUtc> {
+        match &self.kind {
+            ScheduleKind::Cron(cron) => cron.next_after(after, tz),
+            ScheduleKind::Interval { period, anchor } => {
+                let t = after.timestamp_micros().checked_sub(*anchor)?;
+                let k = t.div_euclid(*period).checked_add(1)?;
+                k.checked_mul(*period)?
+                    .checked_add(*anchor)?
+                    .micros_as_unix_timestamp_opt()
+                    .single()
+            }
+        }
+    }
+
+    /// Get the last time of the schedule before `before`, in the zone `tz`.
+    pub fn prev_before<Z: TimeZone>(&self, before: DateTimeUtc, tz: &Z) -> Option<DateTimeUtc> {
+        match &self.kind {
+            ScheduleKind::Cron(cron) => cron.prev_before(before, tz),
+            ScheduleKind::Interval { period, anchor } => {
+                // Round up the nanoseconds, a time equal to `before` is excluded.
+                let ceil = (before.timestamp_subsec_nanos() % 1_000 != 0) as i64;
+                let t = (before.timestamp_micros() + ceil).checked_sub(*anchor)?;
+                let k = (t - 1).div_euclid(*period);
+                k.checked_mul(*period)?
+                    .checked_add(*anchor)?
+                    .micros_as_unix_timestamp_opt()
+                    .single()
+            }
+        }
+    }
+
+    /// Delay a time of the schedule by a random duration in `[0, jitter]`.
+    pub fn jittered(&self, time: DateTimeUtc) -> DateTimeUtc {
+        use std::hash::{BuildHasher, Hasher};
+
+        if self.jitter <= 0 {
+            return time;
+        }
+        // Random keys of `RandomState` are good enough to spread ticks.
+        let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+        hasher.write_i64(time.timestamp_micros());
+        let delay = hasher.finish() % (self.jitter as u64 + 1);
+        time + TimeDelta::microseconds(delay as i64)
+    }
+
+    /// Create a stream which yields the times of the schedule after now in the zone `tz`.
+    ///
+    /// Each time is yielded after sleeping until it plus a jitter, and the times missed
+    /// by a slow consumer are skipped.
+    #[cfg(feature = "tokio")]
+    pub fn stream<Z: TimeZone>(self, tz: Z) -> ScheduleStream<Z> {
+        let next = self.next_after(Utc::now(), &tz);
+        let deadline = next.map_or_else(tokio::time::Instant::now, |x| {
+            sleep_deadline(self.jittered(x))
+        });
+        ScheduleStream {
+            schedule: self,
+            tz,
+            next,
+            sleep: Box::pin(tokio::time::sleep_until(deadline)),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "tokio")]
+fn sleep_deadline(time: DateTimeUtc) -> tokio::time::Instant {
+    let delay = (time - Utc::now()).to_std().unwrap_or_default();
+    tokio::time::Instant::now() + delay
+}
+
+/// Stream of the times of a [`Schedule`], see [`Schedule::stream`].
+#[cfg(feature = "tokio")]
+pub struct ScheduleStream<Z: TimeZone> {
+    schedule: Schedule,
+    tz: Z,
+    next: Option<DateTimeUtc>,
+    sleep: std::pin::Pin<Box<tokio::time::Sleep>>,
+}
+
+#[cfg(feature = "tokio")]
+impl<Z: TimeZone> ScheduleStream<Z> {
+    /// Get the schedule.
+    #[inline]
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    /// Get the time to be yielded next, without the jitter.
+    #[inline]
+    pub fn next_time(&self) -> Option<DateTimeUtc> {
+        self.next
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<Z: TimeZone + Unpin> tokio_stream::Stream for ScheduleStream<Z> {
+    type Item = DateTimeUtc;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        use std::{future::Future, task::Poll};
+
+        let this = self.get_mut();
+        let Some(time) = this.next else {
+            return Poll::Ready(None);
+        };
+        if this.sleep.as_mut().poll(cx).is_pending() {
+            return Poll::Pending;
+        }
+        this.next = this.schedule.next_after(time.max(Utc::now()), &this.tz);
+        if let Some(next) = this.next {
+            let deadline = sleep_deadline(this.schedule.jittered(next));
+            this.sleep.as_mut().reset(deadline);
+        }
+        Poll::Ready(Some(time))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::datetime::*;
+    use chrono::{FixedOffset, Utc};
+
+    fn utc(s: &str) -> DateTimeUtc {
+        utc_from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_cron_schedule() {
+        let next = |expr: &str, t: &str| {
+            let schedule: Schedule = expr.parse().unwrap();
+            schedule.next_after(utc(t), &Utc).map(utc_into_str)
+        };
+        let prev = |expr: &str, t: &str| {
+            let schedule: Schedule = expr.parse().unwrap();
+            schedule.prev_before(utc(t), &Utc).map(utc_into_str)
+        };
+        let cases = [
+            (
+                "*/15 9-17 * * MON-FRI",
+                "2024-03-08T17:50:00Z",
+                "2024-03-11T09:00:00.000000Z",
+            ),
+            (
+                "30 0 0 1 * *",
+                "2024-01-31T12:00:00Z",
+                "2024-02-01T00:00:30.000000Z",
+            ),
+            (
+                "0 0 13 * 5",
+                "2024-09-01T00:00:00Z",
+                "2024-09-06T00:00:00.000000Z",
+            ),
+            (
+                "0 0 13 * 5",
+                "2024-09-06T00:00:00Z",
+                "2024-09-13T00:00:00.000000Z",
+            ),
+            (
+                "0 0 13 * *",
+                "2024-09-01T00:00:00Z",
+                "2024-09-13T00:00:00.000000Z",
+            ),
+            (
+                "0 0 29 2 *",
+                "2024-03-01T00:00:00Z",
+                "2028-02-29T00:00:00.000000Z",
+            ),
+            (
+                "0 0 * * 7",
+                "2024-03-06T00:00:00Z",
+                "2024-03-10T00:00:00.000000Z",
+            ),
+            (
+                "0 12 * jan,Jul sun",
+                "2024-02-01T00:00:00Z",
+                "2024-07-07T12:00:00.000000Z",
+            ),
+            (
+                "0 1-10/4 * * *",
+                "2024-01-01T05:00:00Z",
+                "2024-01-01T09:00:00.000000Z",
+            ),
+            (
+                "@hourly",
+                "2024-01-01T05:00:00.5Z",
+                "2024-01-01T06:00:00.000000Z",
+            ),
+        ];
+        for (expr, t, v) in cases {
+            assert_eq!(next(expr, t).as_deref(), Some(v), "{} after {}", expr, t);
+        }
+        assert_eq!(
+            prev("*/15 9-17 * * MON-FRI", "2024-03-11T09:00:00Z").as_deref(),
+            Some("2024-03-08T17:45:00.000000Z")
+        );
+        assert_eq!(
+            prev("@yearly", "2024-01-01T00:00:00.5Z").as_deref(),
+            Some("2024-01-01T00:00:00.000000Z")
+        );
+        assert_eq!(next("0 0 30 2 *", "2024-01-01T00:00:00Z"), None);
+
+        // In a fixed offset.
+        let schedule: Schedule = "@daily".parse().unwrap();
+        let tz = FixedOffset::east_opt(8 * 3600).unwrap();
+        let t = utc("2024-03-10T00:00:00Z");
+        assert_eq!(
+            schedule.next_after(t, &tz),
+            Some(utc("2024-03-10T16:00:00Z"))
+        );
+        assert_eq!(
+            schedule.prev_before(t, &tz),
+            Some(utc("2024-03-09T16:00:00Z"))
+        );
+
+        let errors = [
+            ("* * * *", ScheduleError::FieldCount(4)),
+            ("* * * * * * *", ScheduleError::FieldCount(7)),
+            (
+                "60 * * * *",
+                ScheduleError::InvalidField {
+                    field: "minute",
+                    value: "60".into(),
+                },
+            ),
+            (
+                "*/0 * * * *",
+                ScheduleError::InvalidField {
+                    field: "minute",
+                    value: "*/0".into(),
+                },
+            ),
+            (
+                "* 5-1 * * *",
+                ScheduleError::InvalidField {
+                    field: "hour",
+                    value: "5-1".into(),
+                },
+            ),
+            (
+                "* * 0 * *",
+                ScheduleError::InvalidField {
+                    field: "day",
+                    value: "0".into(),
+                },
+            ),
+            (
+                "* * * * MON-FOO",
+                ScheduleError::InvalidField {
+                    field: "weekday",
+                    value: "MON-FOO".into(),
+                },
+            ),
+            (
+                "@often",
+                ScheduleError::InvalidField {
+                    field: "macro",
+                    value: "@often".into(),
+                },
+            ),
+            ("@every 0s", ScheduleError::InvalidInterval("0s".into())),
+            ("@every soon", ScheduleError::InvalidInterval("soon".into())),
+        ];
+        for (expr, e) in errors {
+            assert_eq!(expr.parse::<Schedule>(), Err(e), "{}", expr);
+        }
+        assert_eq!(
+            ScheduleError::FieldCount(4).to_string(),
+            "expected 5 or 6 cron fields, got 4"
+        );
+
+        // DST in New York: 2024-03-10 02:00 -> 03:00, 2024-11-03 02:00 -> 01:00.
+        #[cfg(feature = "tz")]
+        {
+            let ny = tz_from_name("America/New_York").unwrap();
+            let schedule: Schedule = "30 2 * * *".parse().unwrap();
+            let t = schedule
+                .next_after(utc("2024-03-09T12:00:00Z"), &ny)
+                .unwrap();
+            assert_eq!(t, utc("2024-03-10T07:30:00Z"));
+            let t = schedule.next_after(t, &ny).unwrap();
+            assert_eq!(t, utc("2024-03-11T06:30:00Z"));
+
+            let schedule: Schedule = "30 1 * * *".parse().unwrap();
+            let t = schedule
+                .next_after(utc("2024-11-03T04:00:00Z"), &ny)
+                .unwrap();
+            assert_eq!(t, utc("2024-11-03T05:30:00Z"));
+            let t = schedule.next_after(t, &ny).unwrap();
+            assert_eq!(t, utc("2024-11-04T06:30:00Z"));
+            assert_eq!(
+                schedule.prev_before(t, &ny),
+                Some(utc("2024-11-03T05:30:00Z"))
+            );
+            // 01:10 in EST, after 01:30 in EDT.
+            assert_eq!(
+                schedule.prev_before(utc("2024-11-03T06:10:00Z"), &ny),
+                Some(utc("2024-11-03T05:30:00Z"))
+            );
+
+            // Every second, the repeated hour fires once and the gap fires after it.
+            let schedule: Schedule = "* * * * * *".parse().unwrap();
+            let cases = [
+                ("2024-11-03T06:30:00Z", "2024-11-03T07:00:00Z"),
+                ("2024-11-03T05:59:59Z", "2024-11-03T07:00:00Z"),
+                ("2024-11-03T05:30:00Z", "2024-11-03T05:30:01Z"),
+                ("2024-03-10T07:10:00Z", "2024-03-10T07:10:01Z"),
+                ("2024-03-10T06:59:59.5Z", "2024-03-10T07:00:00Z"),
+            ];
+            for (t, v) in cases {
+                assert_eq!(schedule.next_after(utc(t), &ny), Some(utc(v)), "{}", t);
+            }
+            let cases = [
+                ("2024-11-03T07:00:00Z", "2024-11-03T05:59:59Z"),
+                ("2024-11-03T06:30:00Z", "2024-11-03T05:59:59Z"),
+                ("2024-11-03T07:00:00.5Z", "2024-11-03T07:00:00Z"),
+                ("2024-03-10T07:10:00Z", "2024-03-10T07:09:59Z"),
+            ];
+            for (t, v) in cases {
+                assert_eq!(schedule.prev_before(utc(t), &ny), Some(utc(v)), "{}", t);
+            }
+        }
+    }
+
+    #[test]
+    fn test_interval_schedule() {
+        let schedule = Schedule::interval(90_000_000).unwrap().anchor(10_000_000);
+        assert_eq!("@every 1m 30s".parse(), Schedule::interval(90_000_000));
+        assert_eq!(
+            Schedule::interval(-1),
+            Err(ScheduleError::InvalidInterval("-1us".into()))
+        );
+
+        let t = utc("1970-01-01T00:01:40Z");
+        assert_eq!(
+            schedule.next_after(utc_default(), &Utc),
+            Some(utc("1970-01-01T00:00:10Z"))
+        );
+        assert_eq!(
+            schedule.next_after(t, &Utc),
+            Some(utc("1970-01-01T00:03:10Z"))
+        );
+        assert_eq!(
+            schedule.prev_before(t, &Utc),
+            Some(utc("1970-01-01T00:00:10Z"))
+        );
+        assert_eq!(
+            schedule.prev_before(utc("1970-01-01T00:01:40.0000005Z"), &Utc),
+            Some(t)
+        );
+        assert_eq!(
+            schedule.prev_before(utc_default(), &Utc),
+            Some(utc("1969-12-31T23:58:40Z"))
+        );
+
+        assert_eq!(schedule.jittered(t), t);
+        let schedule = schedule.jitter(1_000_000);
+        assert_eq!(schedule.jitter_micros(), 1_000_000);
+        for _ in 0..100 {
+            let v = schedule.jittered(t);
+            assert!(v >= t && v <= t + chrono::TimeDelta::seconds(1));
+        }
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_schedule_stream() {
+        use tokio_stream::StreamExt;
+
+        let start = Utc::now();
+        let mut stream = Schedule::interval(20_000).unwrap().stream(Utc);
+        assert!(stream.next_time().unwrap() > start);
+        let ticks: Vec<_> = (&mut stream).take(3).collect().await;
+        assert_eq!(ticks.len(), 3);
+        assert!(ticks[0] > start && ticks[2] <= Utc::now());
+        assert!(ticks.windows(2).all(|x| x[0] < x[1]));
+        assert_eq!(stream.schedule().jitter_micros(), 0);
+    }
+}
@@ -0,0 +1,237 @@
+//! Time zone aware calendar helpers, with the IANA tz database embedded by `chrono-tz`.
+
+use super::{utc_from_str, zoned_from_local, DateTimeUtc, UnixTimeMicros, UnixTimestampXlf};
+use ::chrono::{prelude::*, Months, TimeDelta};
+
+pub use ::chrono_tz::Tz;
+
+/// Get a time zone by its IANA name, e.g. `Asia/Shanghai`.
+#[inline]
+pub fn tz_from_name(name: &str) -> Option<Tz> {
+    name.parse().ok()
+}
+
+/// Convert a local date and time into a zone.
+///
+/// An ambiguous time at the end of DST is the earlier one, and a time in the gap
+/// at the start of DST is shifted forward by the length of the gap.
+#[inline]
+pub fn tz_from_local(tz: Tz, local: NaiveDateTime) -> DateTime<Tz> {
+    zoned_from_local(&tz, local)
+}
+
+/// Parse a local date and time in a zone, e.g. `2024-03-10 02:30`, `2024-03-10T08:00:00.5`
+/// or `2024-03-10` for the start of the day.
+/// A string with an offset, e.g. `2024-03-10T08:00:00+08:00`, is parsed as RFC 3339.
+pub fn tz_parse_local(s: &str, tz: Tz) -> Option<DateTime<Tz>> {
+    const FORMATS: [&str; 4] = [
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M",
+    ];
+    let s = s.trim();
+    if let Ok(utc) = utc_from_str(s) {
+        return Some(utc.with_timezone(&tz));
+    }
+    let local = FORMATS
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(s, f).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .ok()
+                .map(|d| d.and_time(NaiveTime::MIN))
+        })?;
+    Some(tz_from_local(tz, local))
+}
+
+fn days_since(day: Weekday, start: Weekday) -> u32 {
+    (7 + day.num_days_from_monday() - start.num_days_from_monday()) % 7
+}
+
+fn start_of_date(tz: Tz, date: NaiveDate) -> DateTime<Tz> {
+    tz_from_local(tz, date.and_time(NaiveTime::MIN))
+}
+
+/// Extension for time zone aware calendar math on `DateTimeUtc` and `UnixTimeMicros`.
+///
+/// The end of a period is the last microsecond of it.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::Weekday;
+/// use xelf::datetime::*;
+///
+/// let tz = tz_from_name("America/New_York").unwrap();
+/// let t = utc_from_str("2024-03-10T12:00:00Z").unwrap();
+/// assert_eq!(t.start_of_day(tz), utc_from_str("2024-03-10T05:00:00Z").unwrap());
+/// // The day is 23 hours long by the start of DST.
+/// assert_eq!(t.end_of_day(tz), utc_from_str("2024-03-11T03:59:59.999999Z").unwrap());
+/// assert_eq!(t.add_days(tz, -1), utc_from_str("2024-03-09T13:00:00Z").unwrap());
+/// assert_eq!(
+///     t.start_of_week(tz, Weekday::Mon),
+///     utc_from_str("2024-03-04T05:00:00Z").unwrap()
+/// );
+/// ```
+pub trait TimeZoneXlf: Sized {
+    /// Convert into the date and time in a zone.
+    fn in_zone(&self, tz: Tz) -> DateTime<Tz>;
+
+    /// Convert from the date and time in a zone.
+    fn from_zoned(zoned: DateTime<Tz>) -> Self;
+
+    /// Parse a local date and time in a zone, see [`tz_parse_local`].
+    fn parse_local(s: &str, tz: Tz) -> Option<Self> {
+        tz_parse_local(s, tz).map(Self::from_zoned)
+    }
+
+    /// Get the start of the day in a zone.
+    fn start_of_day(&self, tz: Tz) -> Self {
+        Self::from_zoned(start_of_date(tz, self.in_zone(tz).date_naive()))
+    }
+
+    /// Get the end of the day in a zone.
+    fn end_of_day(&self, tz: Tz) -> Self {
+        let next = self.in_zone(tz).date_naive() + TimeDelta::days(1);
+        Self::from_zoned(start_of_date(tz, next) - TimeDelta::microseconds(1))
+    }
+
+    /// Get the start of the week in a zone, which starts on `week_start`.
+    fn start_of_week(&self, tz: Tz, week_start: Weekday) -> Self {
+        let date = self.in_zone(tz).date_naive();
+        let days = days_since(date.weekday(), week_start);
+        Self::from_zoned(start_of_date(tz, date - TimeDelta::days(days as i64)))
+    }
+
+    /// Get the end of the week in a zone, which starts on `week_start`.
+    fn end_of_week(&self, tz: Tz, week_start: Weekday) -> Self {
+        let date = self.in_zone(tz).date_naive();
+        let days = 7 - days_since(date.weekday(), week_start);
+        let next = start_of_date(tz, date + TimeDelta::days(days as i64));
+        Self::from_zoned(next - TimeDelta::microseconds(1))
+    }
+
+    /// Get the start of the month in a zone.
+    fn start_of_month(&self, tz: Tz) -> Self {
+        let date = self
+            .in_zone(tz)
+            .date_naive()
+            .with_day(1)
+            .unwrap_or_default();
+        Self::from_zoned(start_of_date(tz, date))
+    }
+
+    /// Get the end of the month in a zone.
+    fn end_of_month(&self, tz: Tz) -> Self {
+        let date = self.in_zone(tz).date_naive();
+        let next = date
+            .with_day(1)
+            .and_then(|x| x.checked_add_months(Months::new(1)))
+            .unwrap_or(NaiveDate::MAX);
+        Self::from_zoned(start_of_date(tz, next) - TimeDelta::microseconds(1))
+    }
+
+    /// Add days in a zone, keeping the local time across DST changes,
+    /// see [`tz_from_local`] for times which do not exist or are ambiguous.
+    fn add_days(&self, tz: Tz, days: i64) -> Self {
+        let local = self.in_zone(tz).naive_local() + TimeDelta::days(days);
+        Self::from_zoned(tz_from_local(tz, local))
+    }
+}
+
+impl TimeZoneXlf for DateTimeUtc {
+    #[inline]
+    fn in_zone(&self, tz: Tz) -> DateTime<Tz> {
+        self.with_timezone(&tz)
+    }
+
+    #[inline]
+    fn from_zoned(zoned: DateTime<Tz>) -> Self {
+        zoned.with_timezone(&Utc)
+    }
+}
+
+impl TimeZoneXlf for UnixTimeMicros {
+    #[inline]
+    fn in_zone(&self, tz: Tz) -> DateTime<Tz> {
+        self.micros_as_unix_timestamp().with_timezone(&tz)
+    }
+
+    #[inline]
+    fn from_zoned(zoned: DateTime<Tz>) -> Self {
+        zoned.timestamp_micros()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::datetime::*;
+    use chrono::Weekday;
+
+    fn utc(s: &str) -> DateTimeUtc {
+        utc_from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_tz() {
+        let ny = tz_from_name("America/New_York").unwrap();
+        let sh = tz_from_name("Asia/Shanghai").unwrap();
+        assert!(tz_from_name("Mars/Base").is_none());
+
+        let t = utc("2024-01-31T20:00:00Z");
+        assert_eq!(t.start_of_day(sh), utc("2024-01-31T16:00:00Z"));
+        assert_eq!(t.end_of_day(sh), utc("2024-02-01T15:59:59.999999Z"));
+        assert_eq!(t.start_of_month(sh), utc("2024-01-31T16:00:00Z"));
+        assert_eq!(t.end_of_month(sh), utc("2024-02-29T15:59:59.999999Z"));
+        assert_eq!(t.start_of_month(ny), utc("2024-01-01T05:00:00Z"));
+        assert_eq!(
+            t.start_of_week(sh, Weekday::Sun),
+            utc("2024-01-27T16:00:00Z")
+        );
+        assert_eq!(
+            t.end_of_week(sh, Weekday::Sun),
+            utc("2024-02-03T15:59:59.999999Z")
+        );
+
+        // DST in New York: 2024-03-10 02:00 -> 03:00, 2024-11-03 02:00 -> 01:00.
+        let t = utc("2024-03-09T07:30:00Z");
+        assert_eq!(t.in_zone(ny).to_string(), "2024-03-09 02:30:00 EST");
+        assert_eq!(t.add_days(ny, 1), utc("2024-03-10T07:30:00Z"));
+        assert_eq!(t.add_days(ny, 2), utc("2024-03-11T06:30:00Z"));
+        let t = utc("2024-11-02T05:30:00Z");
+        assert_eq!(t.add_days(ny, 1), utc("2024-11-03T05:30:00Z"));
+        assert_eq!(
+            DateTimeUtc::parse_local("2024-11-03 01:30", ny),
+            Some(utc("2024-11-03T05:30:00Z"))
+        );
+        assert_eq!(
+            DateTimeUtc::parse_local("2024-03-10T02:30:00", ny),
+            Some(utc("2024-03-10T07:30:00Z"))
+        );
+        assert_eq!(
+            DateTimeUtc::parse_local("2024-03-10", sh),
+            Some(utc("2024-03-09T16:00:00Z"))
+        );
+        assert_eq!(
+            DateTimeUtc::parse_local("2024-03-10T08:00:00+08:00", ny),
+            Some(utc("2024-03-10T00:00:00Z"))
+        );
+        assert_eq!(DateTimeUtc::parse_local("03/10/2024", ny), None);
+
+        // A day which starts in a DST gap, Santiago 2022-09-11 00:00 -> 01:00.
+        let scl = tz_from_name("America/Santiago").unwrap();
+        let t = utc("2022-09-11T12:00:00Z");
+        assert_eq!(t.start_of_day(scl), utc("2022-09-11T04:00:00Z"));
+
+        let micros = utc("2024-01-31T20:00:00Z").timestamp_micros();
+        assert_eq!(
+            micros.start_of_day(sh),
+            utc("2024-01-31T16:00:00Z").timestamp_micros()
+        );
+        assert_eq!(
+            UnixTimeMicros::parse_local("2024-01-01 08:00:00.5", sh),
+            Some(utc("2024-01-01T00:00:00.5Z").timestamp_micros())
+        );
+    }
+}
@@ -41,6 +41,21 @@ where
 {
     /// Merges properties from a JSON object into the current Model, with an optional skip list.
     fn merge_from_json<S, C>(&mut self, jsn: Json, skip: &S) -> DbResult<()>
+    where
+        S: ?Sized + Contains<C, str>,
+        C: Eq + Ord + Hash + Borrow<str>,
+    {
+        self.merge_from_json_with(jsn, skip, KeyMatch::Exact)
+    }
+
+    /// Merges properties from a JSON object into the current Model, with an optional skip list,
+    /// matching the JSON keys to the column names by `key_match`, e.g. camelCase keys to snake_case columns.
+    fn merge_from_json_with<S, C>(
+        &mut self,
+        jsn: Json,
+        skip: &S,
+        key_match: KeyMatch,
+    ) -> DbResult<()>
     where
         S: ?Sized + Contains<C, str>,
         C: Eq + Ord + Hash + Borrow<str>;
@@ -58,6 +73,21 @@ where
 {
     /// Merges properties from a JSON object into the current ActiveModel, with an optional skip list.
     fn merge_from_json<S, C>(&mut self, jsn: Json, skip: &S) -> DbResult<()>
+    where
+        S: ?Sized + Contains<C, str>,
+        C: Eq + Ord + Hash + Borrow<str>,
+    {
+        self.merge_from_json_with(jsn, skip, KeyMatch::Exact)
+    }
+
+    /// Merges properties from a JSON object into the current ActiveModel, with an optional skip list,
+    /// matching the JSON keys to the column names by `key_match`, e.g. camelCase keys to snake_case columns.
+    fn merge_from_json_with<S, C>(
+        &mut self,
+        jsn: Json,
+        skip: &S,
+        key_match: KeyMatch,
+    ) -> DbResult<()>
     where
         S: ?Sized + Contains<C, str>,
         C: Eq + Ord + Hash + Borrow<str>;
@@ -73,14 +103,24 @@ where
 
 macro_rules! impl_merge_from {
     ($M:ident, $A:ident) => {
-        fn merge_from_json<S, C>(&mut self, jsn: Json, skip: &S) -> DbResult<()>
+        fn merge_from_json_with<S, C>(
+            &mut self,
+            jsn: Json,
+            skip: &S,
+            key_match: KeyMatch,
+        ) -> DbResult<()>
         where
             S: ?Sized + Contains<C, str>,
             C: Eq + Ord + Hash + Borrow<str>,
         {
-            let Some(map) = jsn.as_object() else {
+            let Json::Object(map) = jsn else {
                 return Err(DbErr::Type("Invalid JSON object".to_owned()));
             };
+            let columns: Vec<String> =
+                <<$M::Entity as EntityTrait>::Column as sea_orm::Iterable>::iter()
+                    .map(|col| col.to_string())
+                    .collect();
+            let map = key_match.align(map, columns.iter().map(String::as_str));
 
             // Mark down which attribute exists in the JSON object
             let json_keys: Vec<<$M::Entity as EntityTrait>::Column> =
@@ -92,8 +132,8 @@ macro_rules! impl_merge_from {
                     .collect();
 
             // Convert JSON object into ActiveModel via Model
-            let m: <$M::Entity as EntityTrait>::Model =
-                serde_json::from_value(jsn).map_err(|e| DbErr::Json(e.to_string()))?;
+            let m: <$M::Entity as EntityTrait>::Model = serde_json::from_value(Json::Object(map))
+                .map_err(|e| DbErr::Json(e.to_string()))?;
 
             for col in json_keys {
                 self.set(col, m.get(col));
@@ -141,6 +181,13 @@ where
     }
 }
 
+/// Validation errors of a JSON object, e.g. checked before `merge_from_json`.
+impl From<JsonValidationErrors> for DbErr {
+    fn from(e: JsonValidationErrors) -> Self {
+        DbErr::Json(e.to_string())
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 
 /// Wrapper structure for custom database identifiers, allowing raw string passing as SQL identifiers or function names.
@@ -177,6 +224,8 @@ pub enum DbFunc {
     Upper,
     /// Converts string to lowercase (LOWER).
     Lower,
+    /// Truncates a timestamp to a [TimeUnit] (Postgres: DATE_TRUNC, MySQL: DATE_FORMAT, SQLite: STRFTIME).
+    DateTrunc,
 }
 
 /// Abstract trait for database backend behavior, providing cross-backend SQL functions, table lock SQL generation, and conditional helpers.
@@ -246,6 +295,19 @@ pub trait DbBackendTrait {
     {
         Func::cust(IdenStr(_db_builtin_func(self.backend(), DbFunc::Greatest))).arg(arg)
     }
+
+    /// Constructs an expression that truncates a timestamp to a [TimeUnit] in the session time zone,
+    /// matching [TimeBucketXlf::truncate_to] if it's UTC; weeks start on Monday.
+    ///
+    /// The argument must be a date/time value, which is TEXT in SQLite, where `STRFTIME`
+    /// would read an integer as a Julian day. Integer columns such as `UnixTimeMicros`
+    /// are not supported, truncate their values with [TimeBucketXlf::truncate_to] instead.
+    fn date_trunc<T>(&self, unit: TimeUnit, arg: T) -> SimpleExpr
+    where
+        T: Into<SimpleExpr>,
+    {
+        _db_date_trunc(self.backend(), unit, arg.into())
+    }
 }
 
 /// Extension trait for database connections and transactions, supporting low-level operations like table locking.
@@ -281,6 +343,8 @@ fn _db_builtin_func(backend: DbBackend, func: DbFunc) -> &'static str {
             DbFunc::Greatest => "GREATEST",
             DbFunc::Upper => "UPPER",
             DbFunc::Lower => "LOWER",
+            DbFunc::DateTrunc if backend == DbBackend::MySql => "DATE_FORMAT",
+            DbFunc::DateTrunc => "DATE_TRUNC",
         },
         DbBackend::Sqlite => match func {
             DbFunc::Now => "CURRENT_TIMESTAMP",
@@ -288,6 +352,7 @@ fn _db_builtin_func(backend: DbBackend, func: DbFunc) -> &'static str {
             DbFunc::Greatest => "MAX",
             DbFunc::Upper => "UPPER",
             DbFunc::Lower => "LOWER",
+            DbFunc::DateTrunc => "STRFTIME",
         },
         _ => match func {
             DbFunc::Now => "CURRENT_TIMESTAMP",
@@ -295,10 +360,59 @@ fn _db_builtin_func(backend: DbBackend, func: DbFunc) -> &'static str {
             DbFunc::Greatest => "GREATEST",
             DbFunc::Upper => "UPPER",
             DbFunc::Lower => "LOWER",
+            DbFunc::DateTrunc => "DATE_TRUNC",
         },
     }
 }
 
+fn _db_date_trunc(backend: DbBackend, unit: TimeUnit, arg: SimpleExpr) -> SimpleExpr {
+    let func = Func::cust(IdenStr(_db_builtin_func(backend, DbFunc::DateTrunc)));
+    match backend {
+        DbBackend::MySql => {
+            let format = match unit {
+                TimeUnit::Second => "%Y-%m-%d %H:%i:%s",
+                TimeUnit::Minute => "%Y-%m-%d %H:%i:00",
+                TimeUnit::Hour => "%Y-%m-%d %H:00:00",
+                TimeUnit::Day | TimeUnit::Week => "%Y-%m-%d 00:00:00",
+            };
+            let sql = match unit {
+                TimeUnit::Week => format!(
+                    "CAST(DATE_FORMAT(? - INTERVAL WEEKDAY(?) DAY, '{}') AS DATETIME)",
+                    format
+                ),
+                _ => format!("CAST(DATE_FORMAT(?, '{}') AS DATETIME)", format),
+            };
+            let args = match unit {
+                TimeUnit::Week => vec![arg.clone(), arg],
+                _ => vec![arg],
+            };
+            Expr::cust_with_exprs(sql, args)
+        }
+        // Only TEXT date/times, an integer argument is a Julian day to `STRFTIME`.
+        DbBackend::Sqlite => {
+            let format = match unit {
+                TimeUnit::Second => "'%Y-%m-%d %H:%M:%S'",
+                TimeUnit::Minute => "'%Y-%m-%d %H:%M:00'",
+                TimeUnit::Hour => "'%Y-%m-%d %H:00:00'",
+                TimeUnit::Day | TimeUnit::Week => "'%Y-%m-%d 00:00:00'",
+            };
+            let func = func.arg(Expr::cust(format)).arg(arg);
+            match unit {
+                // The next Sunday or today, then back to Monday.
+                TimeUnit::Week => func
+                    .arg(Expr::cust("'weekday 0'"))
+                    .arg(Expr::cust("'-6 days'"))
+                    .into(),
+                _ => func.into(),
+            }
+        }
+        _ => func
+            .arg(Expr::cust(format!("'{}'", unit.as_str())))
+            .arg(arg)
+            .into(),
+    }
+}
+
 fn _db_lock_table_sql(backend: DbBackend, table: &str, mode: DbLockMode) -> DbResult<String> {
     match backend {
         DbBackend::Postgres => {
@@ -1177,7 +1291,7 @@ mod tests {
         #[sea_orm(table_name = "t_user")]
         pub struct Model {
             #[sea_orm(primary_key)]
-            #[serde(default)]
+            #[serde(default, with = "serde_x_i64_str")]
             pub id: i64,
             #[serde(default)]
             pub state: RecState,
@@ -1274,6 +1388,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_date_trunc() {
+        let sql = |backend: DbBackend, unit| {
+            RawSqlBuilder::expr_to_string(backend, &backend.date_trunc(unit, Expr::cust("t")))
+        };
+        assert_eq!(
+            sql(DbBackend::Postgres, TimeUnit::Hour),
+            "DATE_TRUNC('hour', t)"
+        );
+        assert_eq!(
+            sql(DbBackend::MySql, TimeUnit::Minute),
+            "CAST(DATE_FORMAT(t, '%Y-%m-%d %H:%i:00') AS DATETIME)"
+        );
+        assert_eq!(
+            sql(DbBackend::MySql, TimeUnit::Week),
+            "CAST(DATE_FORMAT(t - INTERVAL WEEKDAY(t) DAY, '%Y-%m-%d 00:00:00') AS DATETIME)"
+        );
+        assert_eq!(
+            sql(DbBackend::Sqlite, TimeUnit::Day),
+            "STRFTIME('%Y-%m-%d 00:00:00', t)"
+        );
+        assert_eq!(
+            sql(DbBackend::Sqlite, TimeUnit::Week),
+            "STRFTIME('%Y-%m-%d 00:00:00', t, 'weekday 0', '-6 days')"
+        );
+        assert_eq!(DbBackend::Sqlite.func_name(DbFunc::DateTrunc), "STRFTIME");
+    }
+
     #[test]
     fn test_active_model() {
         let mut jsn = json!({
@@ -1313,4 +1455,72 @@ mod tests {
         m.merge_from_json(jsn.clone(), &None::<&str>).unwrap();
         println!("{:?}", &m);
     }
+
+    #[test]
+    fn test_db_err_from_validation() {
+        // Validate before merging.
+        let schema = JsonSchema::object()
+            .required_property("name", JsonSchema::string().min_length(1))
+            .property("state", JsonSchema::integer());
+        let merge = |m: &mut user::Model, jsn: Json| -> DbResult<()> {
+            schema.validate(&jsn)?;
+            m.merge_from_json(jsn, &None::<&str>)
+        };
+        let mut m = user::Model::default();
+        let err = merge(&mut m, json!({"name": "", "state": "x"})).unwrap_err();
+        assert_eq!(
+            err,
+            DbErr::Json(
+                "name: must have at least 1 characters; state: expected integer, found string"
+                    .to_owned()
+            )
+        );
+        assert_eq!(m, user::Model::default());
+        merge(&mut m, json!({"id": 1, "name": "system", "state": 1})).unwrap();
+        assert_eq!(m.name.as_deref(), Some("system"));
+    }
+
+    #[test]
+    fn test_merge_key_match() {
+        // camelCase keys are matched to snake_case columns.
+        let m = user::Model {
+            id: 100,
+            name: Some("system".to_owned()),
+            birth_year: Some(2000),
+            create_time: utc_from_str("2022-01-01T01:02:03.123456Z").unwrap(),
+            ..Default::default()
+        };
+        let mut camel = serde_json::to_value(&m).unwrap();
+        camel.rename_keys(KeyCase::Camel);
+        assert!(camel.get("createTime").is_some() && camel.get("birthYear").is_some());
+
+        let mut m2 = user::Model::default();
+        m2.merge_from_json(camel.clone(), &None::<&str>).unwrap();
+        assert_eq!(m2.birth_year, None);
+        m2.merge_from_json_with(camel, &None::<&str>, KeyMatch::Case(KeyCase::Snake))
+            .unwrap();
+        assert_eq!(m2, m);
+    }
+
+    #[test]
+    fn test_i64_str_model() {
+        // IDs are serialized as strings, and are accepted in both forms.
+        let m = user::Model {
+            id: i64::MAX,
+            name: Some("system".to_owned()),
+            ..Default::default()
+        };
+        let jsn = serde_json::to_value(&m).unwrap();
+        assert_eq!(jsn["id"], "9223372036854775807");
+        let mut m2 = user::Model::default();
+        m2.merge_from_json(jsn.clone(), &None::<&str>).unwrap();
+        assert_eq!(m2, m);
+
+        let mut am = <user::ActiveModel as Default>::default();
+        am.merge_from_json(json!({"id": 100, "name": "x"}), &None::<&str>)
+            .unwrap();
+        assert_eq!(am.id, Set(100));
+        am.merge_from_json(jsn, &None::<&str>).unwrap();
+        assert_eq!(am.id, Set(i64::MAX));
+    }
 }
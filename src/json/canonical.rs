@@ -0,0 +1,204 @@
+//! Canonical JSON serialization (RFC 8785 JCS) and stable content hashing.
+
+use ::serde_json::{map::Map, Number, Value as Json};
+use ::std::io::{self, Write};
+
+/// Write a JSON value in the canonical form of RFC 8785 JSON Canonicalization Scheme:
+/// no whitespaces, object keys sorted by UTF-16 code units, and numbers formatted like
+/// ECMAScript.
+///
+/// As RFC 8785 requires, all numbers are IEEE 754 doubles, so integers out of the safe range
+/// (±2^53) are rounded, e.g. `9007199254740993` is written as `9007199254740992`.
+/// Large IDs should be strings in payloads to be signed or hashed.
+pub fn to_canonical_writer<W: Write>(mut writer: W, jsn: &Json) -> io::Result<()> {
+    write_value(&mut writer, jsn)
+}
+
+pub(super) fn write_map<W: Write>(w: &mut W, map: &Map<String, Json>) -> io::Result<()> {
+    let mut entries: Vec<(&String, &Json)> = map.iter().collect();
+    entries.sort_by(|a, b| a.0.encode_utf16().cmp(b.0.encode_utf16()));
+    w.write_all(b"{")?;
+    for (i, (k, v)) in entries.into_iter().enumerate() {
+        if i > 0 {
+            w.write_all(b",")?;
+        }
+        serde_json::to_writer(&mut *w, k)?;
+        w.write_all(b":")?;
+        write_value(w, v)?;
+    }
+    w.write_all(b"}")
+}
+
+fn write_value<W: Write>(w: &mut W, jsn: &Json) -> io::Result<()> {
+    match jsn {
+        Json::Number(n) => write!(w, "{}", format_number(n)),
+        Json::Array(array) => {
+            w.write_all(b"[")?;
+            for (i, v) in array.iter().enumerate() {
+                if i > 0 {
+                    w.write_all(b",")?;
+                }
+                write_value(w, v)?;
+            }
+            w.write_all(b"]")
+        }
+        Json::Object(map) => write_map(w, map),
+        // The escaping of serde_json is the same as JCS.
+        _ => Ok(serde_json::to_writer(w, jsn)?),
+    }
+}
+
+fn format_number(n: &Number) -> String {
+    n.as_f64().map_or_else(|| n.to_string(), format_f64)
+}
+
+/// Format a finite `f64` like ECMAScript `Number.prototype.toString()`.
+fn format_f64(v: f64) -> String {
+    if v == 0.0 {
+        return "0".to_owned();
+    }
+    // The shortest round-trip digits and the exponent.
+    let s = format!("{:e}", v.abs());
+    let (mantissa, exp) = s.split_once('e').unwrap_or((&s, "0"));
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let k = digits.len() as i32;
+    let n = exp.parse::<i32>().unwrap_or(0) + 1;
+
+    let mut out = String::new();
+    if v < 0.0 {
+        out.push('-');
+    }
+    if k <= n && n <= 21 {
+        out.push_str(&digits);
+        out.push_str(&"0".repeat((n - k) as usize));
+    } else if 0 < n && n <= 21 {
+        out.push_str(&digits[..n as usize]);
+        out.push('.');
+        out.push_str(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        out.push_str("0.");
+        out.push_str(&"0".repeat(-n as usize));
+        out.push_str(&digits);
+    } else {
+        out.push_str(&digits[..1]);
+        if k > 1 {
+            out.push('.');
+            out.push_str(&digits[1..]);
+        }
+        out.push('e');
+        out.push(if n > 0 { '+' } else { '-' });
+        out.push_str(&(n - 1).abs().to_string());
+    }
+    out
+}
+
+/// Writer that computes the 64-bit FNV-1a hash of the bytes written,
+/// which is stable across platforms and versions.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct Fnv1aWriter(pub(super) u64);
+
+impl Default for Fnv1aWriter {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Write for Fnv1aWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for &b in buf {
+            self.0 ^= b as u64;
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_f64;
+    use crate::json::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_canonical_json() {
+        let cases = [
+            (0.0, "0"),
+            (-0.0, "0"),
+            (1.0, "1"),
+            (-1.5, "-1.5"),
+            (1e21, "1e+21"),
+            (1e20, "100000000000000000000"),
+            (123456789012345680000.0, "123456789012345680000"),
+            (0.000001, "0.000001"),
+            (1e-7, "1e-7"),
+            (4.5e-10, "4.5e-10"),
+            (1.2345e30, "1.2345e+30"),
+            (0.1 + 0.2, "0.30000000000000004"),
+            (f64::MAX, "1.7976931348623157e+308"),
+            (5e-324, "5e-324"),
+        ];
+        for (v, s) in cases {
+            assert_eq!(format_f64(v), s);
+        }
+
+        // The example of RFC 8785 section 3.2.2, numbers are not parsed by serde_json
+        // which may be off by one ULP without the `float_roundtrip` feature.
+        let mut jsn: Json = serde_json::from_str(
+            r#"{
+                "string": "€$\u000F\u000aA'B\"\\\\\"\/",
+                "literals": [null, true, false]
+            }"#,
+        )
+        .unwrap();
+        jsn.insert_s(
+            "numbers",
+            [
+                333_333_333.333_333_3,
+                1E30,
+                4.50,
+                2e-3,
+                0.000000000000000000000000001,
+            ],
+        );
+        assert_eq!(
+            jsn.to_canonical_string(),
+            r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#
+        );
+
+        // Keys are sorted by UTF-16 code units.
+        let jsn = json!({"\u{e9}": 1, "\u{1f600}": 2, "\u{fb33}": 3, "a": {"b": 1, "a": [2]}});
+        assert_eq!(
+            jsn.to_canonical_string(),
+            "{\"a\":{\"a\":[2],\"b\":1},\"\u{e9}\":1,\"\u{1f600}\":2,\"\u{fb33}\":3}"
+        );
+        assert_eq!(
+            jsn.to_canonical_vec(),
+            jsn.to_canonical_string().into_bytes()
+        );
+        let map = jsn.as_object().unwrap();
+        assert_eq!(map.to_canonical_string(), jsn.to_canonical_string());
+        // Integers are doubles as ECMAScript.
+        assert_eq!(
+            json!(u64::MAX).to_canonical_string(),
+            "18446744073709552000"
+        );
+        assert_eq!(
+            json!([9007199254740993_i64, -9007199254740993_i64, 1_i64 << 53]).to_canonical_string(),
+            "[9007199254740992,-9007199254740992,9007199254740992]"
+        );
+
+        // Hashes are independent of the key order.
+        let a: Json =
+            serde_json::from_str(r#"{"x": 1, "y": [1.0, {"q": null, "p": "s"}]}"#).unwrap();
+        let b: Json = serde_json::from_str(r#"{"y": [1, {"p": "s", "q": null}], "x": 1}"#).unwrap();
+        assert_eq!(a.canonical_hash(), b.canonical_hash());
+        assert_eq!(map.canonical_hash(), jsn.canonical_hash());
+        assert_ne!(a.canonical_hash(), json!({"x": 1}).canonical_hash());
+        assert_eq!(json!("").canonical_hash(), 0x07cc_7607_b494_9e25);
+    }
+}
@@ -0,0 +1,252 @@
+//! Key casing conversions and key matching of JSON objects.
+
+use ::serde_json::{map::Map, Value as Json};
+
+/// Casing styles of JSON keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum KeyCase {
+    /// `userName`
+    Camel,
+    /// `user_name`
+    Snake,
+    /// `user-name`
+    Kebab,
+    /// `UserName`
+    Pascal,
+}
+
+impl KeyCase {
+    /// Convert a key into this case, leading underscores are kept.
+    ///
+    /// Words are split at `_`, `-`, whitespaces and case boundaries,
+    /// an acronym is a single word.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xelf::json::*;
+    ///
+    /// assert_eq!(KeyCase::Snake.convert("parseHTTPRequest2Body"), "parse_http_request2_body");
+    /// assert_eq!(KeyCase::Camel.convert("user_name"), "userName");
+    /// assert_eq!(KeyCase::Kebab.convert("UserID"), "user-id");
+    /// assert_eq!(KeyCase::Pascal.convert("_created-at"), "_CreatedAt");
+    /// ```
+    pub fn convert(self, key: &str) -> String {
+        let body = key.trim_start_matches('_');
+        let mut s = key[..key.len() - body.len()].to_owned();
+        for (i, word) in split_words(body).iter().enumerate() {
+            match self {
+                Self::Snake | Self::Kebab => {
+                    if i > 0 {
+                        s.push(if self == Self::Snake { '_' } else { '-' });
+                    }
+                    s.extend(word.chars().flat_map(char::to_lowercase));
+                }
+                Self::Camel | Self::Pascal => {
+                    let mut chars = word.chars();
+                    if let Some(c) = chars.next() {
+                        if i == 0 && self == Self::Camel {
+                            s.extend(c.to_lowercase());
+                        } else {
+                            s.extend(c.to_uppercase());
+                        }
+                    }
+                    s.extend(chars.flat_map(char::to_lowercase));
+                }
+            }
+        }
+        s
+    }
+}
+
+fn split_words(s: &str) -> Vec<&str> {
+    let chars: Vec<(usize, char)> = s.char_indices().collect();
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, &(pos, c)) in chars.iter().enumerate() {
+        if c == '_' || c == '-' || c.is_whitespace() {
+            if let Some(x) = start.take() {
+                words.push(&s[x..pos]);
+            }
+            continue;
+        }
+        if let Some(x) = start {
+            let prev = chars[i - 1].1;
+            let next_lower = chars.get(i + 1).is_some_and(|x| x.1.is_lowercase());
+            if c.is_uppercase()
+                && (prev.is_lowercase() || prev.is_numeric() || prev.is_uppercase() && next_lower)
+            {
+                words.push(&s[x..pos]);
+                start = Some(pos);
+            }
+        } else {
+            start = Some(pos);
+        }
+    }
+    if let Some(x) = start {
+        words.push(&s[x..]);
+    }
+    words
+}
+
+/// Strategies to match keys of a JSON object to field names.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum KeyMatch {
+    /// Keys must be equal to field names.
+    #[default]
+    Exact,
+    /// Keys are compared ASCII case-insensitively.
+    IgnoreCase,
+    /// Keys and field names are compared after converting into the case,
+    /// e.g. `userName` matches `user_name`.
+    Case(KeyCase),
+}
+
+impl KeyMatch {
+    /// Check if a key matches a field name.
+    pub fn matches(self, key: &str, name: &str) -> bool {
+        match self {
+            Self::Exact => key == name,
+            Self::IgnoreCase => key.eq_ignore_ascii_case(name),
+            Self::Case(case) => key == name || case.convert(key) == case.convert(name),
+        }
+    }
+
+    /// Find the value of a field name in an object, an exact match is preferred.
+    pub fn find<'a>(self, map: &'a Map<String, Json>, name: &str) -> Option<&'a Json> {
+        map.get(name).or_else(|| match self {
+            Self::Exact => None,
+            _ => map
+                .iter()
+                .find_map(|(k, v)| self.matches(k, name).then_some(v)),
+        })
+    }
+
+    /// Rename keys of an object which match the field names into the field names,
+    /// other keys are kept.
+    pub fn align<'a, I>(self, map: Map<String, Json>, names: I) -> Map<String, Json>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        if self == Self::Exact {
+            return map;
+        }
+        let mut keys: Vec<Option<String>> = map.keys().map(|k| Some(k.clone())).collect();
+        let mut renamed = Vec::new();
+        for name in names {
+            let pos = keys
+                .iter()
+                .position(|k| k.as_deref() == Some(name))
+                .or_else(|| {
+                    keys.iter()
+                        .position(|k| k.as_deref().is_some_and(|k| self.matches(k, name)))
+                });
+            if let Some(pos) = pos {
+                renamed.push((keys[pos].take().unwrap(), name.to_owned()));
+            }
+        }
+        let mut map = map;
+        for (key, name) in renamed {
+            if let Some(v) = map.remove(&key) {
+                map.insert(name, v);
+            }
+        }
+        map
+    }
+}
+
+/// Recursively rename keys of all objects in a JSON value.
+pub(super) fn rename_keys(jsn: &mut Json, f: &mut dyn FnMut(&str) -> String) {
+    match jsn {
+        Json::Object(map) => rename_map_keys(map, f),
+        Json::Array(array) => {
+            for v in array {
+                rename_keys(v, f);
+            }
+        }
+        _ => (),
+    }
+}
+
+pub(super) fn rename_map_keys(map: &mut Map<String, Json>, f: &mut dyn FnMut(&str) -> String) {
+    *map = std::mem::take(map)
+        .into_iter()
+        .map(|(k, mut v)| {
+            rename_keys(&mut v, f);
+            (f(&k), v)
+        })
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_key_case() {
+        let cases = [
+            ("userName", "user_name", "user-name", "UserName"),
+            (
+                "xmlHttpRequest",
+                "xml_http_request",
+                "xml-http-request",
+                "XmlHttpRequest",
+            ),
+            ("id", "id", "id", "Id"),
+            ("ipv4Address", "ipv4_address", "ipv4-address", "Ipv4Address"),
+            ("", "", "", ""),
+        ];
+        for (camel, snake, kebab, pascal) in cases {
+            for s in [camel, snake, kebab, pascal] {
+                assert_eq!(KeyCase::Camel.convert(s), camel);
+                assert_eq!(KeyCase::Snake.convert(s), snake);
+                assert_eq!(KeyCase::Kebab.convert(s), kebab);
+                assert_eq!(KeyCase::Pascal.convert(s), pascal);
+            }
+        }
+        assert_eq!(KeyCase::Snake.convert("XMLHttpRequest"), "xml_http_request");
+        assert_eq!(KeyCase::Camel.convert("__meta data"), "__metaData");
+
+        let mut jsn = json!({"userId": 1, "addressList": [{"zipCode": "1"}], "_raw": {"a-b": 2}});
+        jsn.rename_keys(KeyCase::Snake);
+        assert_eq!(
+            jsn,
+            json!({"user_id": 1, "address_list": [{"zip_code": "1"}], "_raw": {"a_b": 2}})
+        );
+        jsn.rename_keys_with(|k| k.to_uppercase());
+        assert_eq!(
+            jsn,
+            json!({"USER_ID": 1, "ADDRESS_LIST": [{"ZIP_CODE": "1"}], "_RAW": {"A_B": 2}})
+        );
+    }
+
+    #[test]
+    fn test_key_match() {
+        let src = json!({"userName": "tom", "AGE": 3, "user_name": "jerry"});
+        let map = src.as_object().unwrap();
+        assert_eq!(KeyMatch::Exact.find(map, "age"), None);
+        assert_eq!(KeyMatch::IgnoreCase.find(map, "age"), Some(&json!(3)));
+        let case = KeyMatch::Case(KeyCase::Snake);
+        assert_eq!(case.find(map, "user_name"), Some(&json!("jerry")));
+        assert!(case.matches("user-name", "UserName"));
+        assert!(!KeyMatch::IgnoreCase.matches("user_name", "userName"));
+
+        let map = json!({"userName": "tom", "AGE": 3, "other": 0});
+        let map = case.align(map.as_object().unwrap().clone(), ["user_name", "age"]);
+        assert_eq!(
+            Json::Object(map),
+            json!({"user_name": "tom", "age": 3, "other": 0})
+        );
+
+        let mut dst = json!({"user_name": "", "age": 0});
+        src.merge_to_with(&mut dst, &None::<&str>, KeyMatch::IgnoreCase)
+            .unwrap();
+        assert_eq!(dst, json!({"user_name": "jerry", "age": 3}));
+        let mut dst = json!({"user_name": "", "age": 0});
+        json!({"userName": "tom", "Age": 3})
+            .merge_to_with(&mut dst, &["age"], KeyMatch::Case(KeyCase::Camel))
+            .unwrap();
+        assert_eq!(dst, json!({"user_name": "tom", "age": 0}));
+    }
+}
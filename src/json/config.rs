@@ -0,0 +1,435 @@
+//! Layered configuration: defaults, JSON files and environment variables,
+//! deserialized into a typed struct.
+
+use super::{update, JsonObjectXlf, JsonPath, KeyMatch};
+use ::serde::{de::DeserializeOwned, ser::Serialize};
+use ::serde_json::{map::Map, Value as Json};
+use ::std::{
+    collections::BTreeMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Where a config value comes from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// The default value.
+    Default,
+    /// A JSON file.
+    File(PathBuf),
+    /// An environment variable.
+    Env(String),
+    /// A variable in a dotenv file.
+    Dotenv { path: PathBuf, var: String },
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Default => f.write_str("default"),
+            Self::File(path) => write!(f, "file {}", path.display()),
+            Self::Env(var) => write!(f, "env {}", var),
+            Self::Dotenv { path, var } => write!(f, "dotenv {}: {}", path.display(), var),
+        }
+    }
+}
+
+/// Error of loading configuration.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// A file can not be read.
+    Io { path: PathBuf, error: io::Error },
+    /// A file can not be parsed.
+    Parse { path: PathBuf, message: String },
+    /// A value can not be converted into the type of the config value.
+    Invalid { key: String, from: ConfigSource },
+    /// The config can not be deserialized into the typed struct.
+    Deserialize(serde_json::Error),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, error } => write!(f, "failed to read {}: {}", path.display(), error),
+            Self::Parse { path, message } => {
+                write!(f, "failed to parse {}: {}", path.display(), message)
+            }
+            Self::Invalid { key, from } => write!(f, "invalid value of {} from {}", key, from),
+            Self::Deserialize(e) => write!(f, "invalid config: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { error, .. } => Some(error),
+            Self::Deserialize(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Loader of layered configuration.
+///
+/// Layers are applied in order with [`deep_update_with`](JsonObjectXlf::deep_update_with),
+/// so values are converted into the types of the defaults, e.g. `"8080"` into a number,
+/// and keys which are not in the defaults are ignored. A value which can not be converted
+/// is an error.
+///
+/// # Examples
+///
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use xelf::json::*;
+///
+/// #[derive(Default, Serialize, Deserialize)]
+/// struct Db {
+///     url: String,
+///     pool_size: u32,
+/// }
+///
+/// #[derive(Default, Serialize, Deserialize)]
+/// struct AppConfig {
+///     debug: bool,
+///     db: Db,
+/// }
+///
+/// let vars = [("APP__DB__URL", "postgres://db"), ("APP__DB__POOL_SIZE", "8"), ("OTHER", "x")];
+/// let loader = ConfigLoader::new(&AppConfig::default())
+///     .unwrap()
+///     .optional_file("/nonexistent/app.json")
+///     .unwrap()
+///     .env_vars("APP", vars.map(|(k, v)| (k.to_owned(), v.to_owned())))
+///     .unwrap();
+/// let config: AppConfig = loader.deserialize().unwrap();
+/// assert_eq!((config.db.url.as_str(), config.db.pool_size), ("postgres://db", 8));
+/// assert_eq!(loader.source_of("db.url"), Some(ConfigSource::Env("APP__DB__URL".to_owned())));
+/// assert_eq!(loader.source_of("debug"), Some(ConfigSource::Default));
+/// ```
+#[derive(Clone, Debug)]
+pub struct ConfigLoader {
+    value: Json,
+    sources: BTreeMap<JsonPath, ConfigSource>,
+}
+
+impl ConfigLoader {
+    /// Separator of nested keys in environment variables, e.g. `APP__DB__URL` for `db.url`.
+    pub const ENV_SEPARATOR: &'static str = "__";
+
+    /// Create a loader with the defaults, usually `T::default()` of the typed struct.
+    pub fn new<T: Serialize>(defaults: &T) -> Result<Self, ConfigError> {
+        let value = serde_json::to_value(defaults).map_err(ConfigError::Deserialize)?;
+        Ok(Self::from_json(value))
+    }
+
+    /// Create a loader with the defaults in JSON.
+    pub fn from_json(defaults: Json) -> Self {
+        Self {
+            value: defaults,
+            sources: BTreeMap::new(),
+        }
+    }
+
+    /// Apply a JSON file, which must exist.
+    pub fn file<P: AsRef<Path>>(self, path: P) -> Result<Self, ConfigError> {
+        self.read_file(path.as_ref(), false)
+    }
+
+    /// Apply a JSON file if it exists.
+    pub fn optional_file<P: AsRef<Path>>(self, path: P) -> Result<Self, ConfigError> {
+        self.read_file(path.as_ref(), true)
+    }
+
+    fn read_file(mut self, path: &Path, optional: bool) -> Result<Self, ConfigError> {
+        let text = match fs::read_to_string(path) {
+            Ok(x) => x,
+            Err(e) if optional && e.kind() == io::ErrorKind::NotFound => return Ok(self),
+            Err(error) => {
+                return Err(ConfigError::Io {
+                    path: path.to_owned(),
+                    error,
+                })
+            }
+        };
+        let jsn: Json = serde_json::from_str(&text).map_err(|e| ConfigError::Parse {
+            path: path.to_owned(),
+            message: e.to_string(),
+        })?;
+        self.apply(jsn, ConfigSource::File(path.to_owned()))?;
+        Ok(self)
+    }
+
+    /// Apply an overlay, e.g. from command line arguments.
+    pub fn apply(&mut self, overlay: Json, source: ConfigSource) -> Result<(), ConfigError> {
+        overlay_value(
+            &mut self.value,
+            overlay,
+            KeyMatch::Exact,
+            &source,
+            &mut self.sources,
+        )
+    }
+
+    /// Apply the environment variables with a prefix, matching keys case-insensitively,
+    /// e.g. `APP__DB__URL` for `db.url` with the prefix `APP`.
+    pub fn env(self, prefix: &str) -> Result<Self, ConfigError> {
+        self.env_vars(prefix, std::env::vars())
+    }
+
+    /// Apply variables like the environment variables, see [`env`](Self::env).
+    pub fn env_vars<I>(mut self, prefix: &str, vars: I) -> Result<Self, ConfigError>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        for (var, value) in vars {
+            self.apply_var(prefix, var, value, ConfigSource::Env)?;
+        }
+        Ok(self)
+    }
+
+    /// Apply the variables with a prefix in a dotenv file if it exists,
+    /// without changing the environment variables of the process.
+    #[cfg(feature = "dotenv")]
+    pub fn dotenv<P: AsRef<Path>>(mut self, path: P, prefix: &str) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let parse_error = |e: dotenv::Error| ConfigError::Parse {
+            path: path.to_owned(),
+            message: e.to_string(),
+        };
+        // The iterator is deprecated in favor of setting the environment variables,
+        // which is not wanted here.
+        #[allow(deprecated)]
+        let iter = match dotenv::from_path_iter(path) {
+            Ok(x) => x,
+            Err(dotenv::Error::Io(e)) if e.kind() == io::ErrorKind::NotFound => return Ok(self),
+            Err(dotenv::Error::Io(error)) => {
+                return Err(ConfigError::Io {
+                    path: path.to_owned(),
+                    error,
+                })
+            }
+            Err(e) => return Err(parse_error(e)),
+        };
+        for item in iter {
+            let (var, value) = item.map_err(parse_error)?;
+            self.apply_var(prefix, var, value, |var| ConfigSource::Dotenv {
+                path: path.to_owned(),
+                var,
+            })?;
+        }
+        Ok(self)
+    }
+
+    fn apply_var<F>(
+        &mut self,
+        prefix: &str,
+        var: String,
+        value: String,
+        f: F,
+    ) -> Result<(), ConfigError>
+    where
+        F: FnOnce(String) -> ConfigSource,
+    {
+        let Some(keys) = var
+            .strip_prefix(prefix)
+            .and_then(|x| x.strip_prefix(Self::ENV_SEPARATOR))
+        else {
+            return Ok(());
+        };
+        let mut overlay = Json::String(value);
+        for key in keys.rsplit(Self::ENV_SEPARATOR) {
+            let mut map = Map::new();
+            map.insert(key.to_lowercase(), overlay);
+            overlay = Json::Object(map);
+        }
+        overlay_value(
+            &mut self.value,
+            overlay,
+            KeyMatch::IgnoreCase,
+            &f(var),
+            &mut self.sources,
+        )
+    }
+
+    /// Get the merged configuration.
+    #[inline]
+    pub fn value(&self) -> &Json {
+        &self.value
+    }
+
+    /// Deserialize the merged configuration into a typed struct.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, ConfigError> {
+        serde_json::from_value(self.value.clone()).map_err(ConfigError::Deserialize)
+    }
+
+    /// Get the source of a value by a dotted path, `None` if there is no such value.
+    pub fn source_of(&self, path: &str) -> Option<ConfigSource> {
+        let path = JsonPath::parse(path).ok()?;
+        self.value.get_path(&path)?;
+        let source = self
+            .sources
+            .iter()
+            .filter(|(k, _)| path.starts_with(k))
+            .max_by_key(|(k, _)| k.segments().len())
+            .map_or(ConfigSource::Default, |(_, v)| v.clone());
+        Some(source)
+    }
+
+    /// Get the sources of all values which are not defaults, by their dotted paths.
+    pub fn sources(&self) -> impl Iterator<Item = (String, &ConfigSource)> {
+        self.sources.iter().map(|(k, v)| (k.to_string(), v))
+    }
+}
+
+/// Overlay a value like `deep_update_with`, recording the sources of updated values.
+fn overlay_value(
+    base: &mut Json,
+    overlay: Json,
+    key_match: KeyMatch,
+    source: &ConfigSource,
+    sources: &mut BTreeMap<JsonPath, ConfigSource>,
+) -> Result<(), ConfigError> {
+    let mut error = None;
+    update::deep_update_value(
+        base,
+        overlay,
+        true,
+        key_match,
+        &mut JsonPath::new(),
+        &mut |path, accepted| {
+            if !accepted {
+                error.get_or_insert_with(|| ConfigError::Invalid {
+                    key: path.to_string(),
+                    from: source.clone(),
+                });
+            } else if error.is_none() {
+                sources.retain(|k, _| !k.starts_with(path));
+                sources.insert(path.clone(), source.clone());
+            }
+        },
+    );
+    error.map_or(Ok(()), Err)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json::*;
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Db {
+        url: String,
+        pool_size: u32,
+        replicas: Vec<String>,
+    }
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct AppConfig {
+        debug: bool,
+        name: Option<String>,
+        #[serde(rename = "logLevel")]
+        log_level: String,
+        db: Db,
+    }
+
+    #[test]
+    fn test_config_loader() {
+        let dir = std::env::temp_dir().join(format!("xelf-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let base = dir.join("base.json");
+        let local = dir.join("local.json");
+        std::fs::write(
+            &base,
+            r#"{"debug": "true", "name": "app", "db": {"url": "a", "pool_size": 4}, "x": 1}"#,
+        )
+        .unwrap();
+        std::fs::write(&local, r#"{"db": {"pool_size": "16"}}"#).unwrap();
+
+        let vars = [
+            ("APP__DB__URL", "b"),
+            ("APP__LOGLEVEL", "debug"),
+            ("APP__DB__REPLICAS", r#"["r1", "r2"]"#),
+            ("APPX__DEBUG", "0"),
+            ("APP__UNKNOWN", "0"),
+        ]
+        .map(|(k, v)| (k.to_owned(), v.to_owned()));
+        let loader = ConfigLoader::new(&AppConfig::default())
+            .unwrap()
+            .file(&base)
+            .unwrap()
+            .optional_file(dir.join("missing.json"))
+            .unwrap()
+            .file(&local)
+            .unwrap()
+            .env_vars("APP", vars)
+            .unwrap();
+        let config: AppConfig = loader.deserialize().unwrap();
+        assert_eq!(
+            config,
+            AppConfig {
+                debug: true,
+                name: Some("app".to_owned()),
+                log_level: "debug".to_owned(),
+                db: Db {
+                    url: "b".to_owned(),
+                    pool_size: 16,
+                    replicas: vec!["r1".to_owned(), "r2".to_owned()],
+                },
+            }
+        );
+        assert_eq!(
+            loader.source_of("debug"),
+            Some(ConfigSource::File(base.clone()))
+        );
+        assert_eq!(
+            loader.source_of("db.pool_size"),
+            Some(ConfigSource::File(local))
+        );
+        assert_eq!(
+            loader.source_of("logLevel"),
+            Some(ConfigSource::Env("APP__LOGLEVEL".to_owned()))
+        );
+        assert_eq!(
+            loader.source_of("db.replicas[1]"),
+            Some(ConfigSource::Env("APP__DB__REPLICAS".to_owned()))
+        );
+        assert_eq!(loader.source_of("db"), Some(ConfigSource::Default));
+        assert_eq!(loader.source_of("x"), None);
+        assert_eq!(loader.sources().count(), 6);
+
+        let err = ConfigLoader::from_json(json!({"db": {"pool_size": 1}}))
+            .env_vars("APP", [("APP__DB__POOL_SIZE".to_owned(), "x".to_owned())])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid value of db.pool_size from env APP__DB__POOL_SIZE"
+        );
+        assert!(matches!(
+            ConfigLoader::from_json(json!({})).file(dir.join("missing.json")),
+            Err(ConfigError::Io { .. })
+        ));
+        std::fs::write(&base, "{").unwrap();
+        assert!(matches!(
+            ConfigLoader::from_json(json!({})).file(&base),
+            Err(ConfigError::Parse { .. })
+        ));
+
+        #[cfg(feature = "dotenv")]
+        {
+            let env = dir.join(".env");
+            std::fs::write(&env, "APP__DEBUG=false\nAPP__DB__URL=c\n").unwrap();
+            let loader = loader.dotenv(&env, "APP").unwrap();
+            assert_eq!(loader.value()["db"]["url"], "c");
+            assert_eq!(
+                loader.source_of("debug"),
+                Some(ConfigSource::Dotenv {
+                    path: env,
+                    var: "APP__DEBUG".to_owned()
+                })
+            );
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
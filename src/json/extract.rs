@@ -0,0 +1,459 @@
+//! Typed extraction from JSON values, with errors reporting the failing paths.
+
+use super::{JsonIndexed, JsonPath};
+use ::serde_json::{json, map::Map, Value as Json};
+use ::std::fmt;
+
+/// Type of a JSON value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum JsonType {
+    Null,
+    Bool,
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+impl JsonType {
+    /// Get the type of a JSON value.
+    pub fn of(jsn: &Json) -> Self {
+        match jsn {
+            Json::Null => Self::Null,
+            Json::Bool(_) => Self::Bool,
+            Json::Number(_) => Self::Number,
+            Json::String(_) => Self::String,
+            Json::Array(_) => Self::Array,
+            Json::Object(_) => Self::Object,
+        }
+    }
+
+    /// Get the name of the type.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Null => "null",
+            Self::Bool => "boolean",
+            Self::Number => "number",
+            Self::String => "string",
+            Self::Array => "array",
+            Self::Object => "object",
+        }
+    }
+}
+
+impl fmt::Display for JsonType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Kind of a [`JsonExtractError`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum JsonExtractErrorKind {
+    /// The path can not be parsed.
+    InvalidPath,
+    /// No value at the path.
+    Missing,
+    /// The value can not be converted into the expected type.
+    Mismatch {
+        expected: &'static str,
+        actual: JsonType,
+    },
+}
+
+/// Error of extracting a typed value from JSON.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JsonExtractError {
+    /// The path of the value, e.g. `a.b[3]`.
+    pub path: String,
+    pub kind: JsonExtractErrorKind,
+}
+
+impl JsonExtractError {
+    /// Create an error that the value at the path does not match the expected type.
+    pub fn mismatch(path: &JsonPath, expected: &'static str, actual: &Json) -> Self {
+        Self {
+            path: path.to_string(),
+            kind: JsonExtractErrorKind::Mismatch {
+                expected,
+                actual: JsonType::of(actual),
+            },
+        }
+    }
+
+    /// Get the message without the path.
+    pub fn reason(&self) -> String {
+        match &self.kind {
+            JsonExtractErrorKind::InvalidPath => "invalid path".to_owned(),
+            JsonExtractErrorKind::Missing => "missing value".to_owned(),
+            JsonExtractErrorKind::Mismatch { expected, actual } => {
+                format!("expected {}, found {}", expected, actual)
+            }
+        }
+    }
+}
+
+impl fmt::Display for JsonExtractError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.reason())
+    }
+}
+
+impl std::error::Error for JsonExtractError {}
+
+/// Errors of all fields collected by [`JsonExtractor`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct JsonExtractErrors(pub Vec<JsonExtractError>);
+
+impl JsonExtractErrors {
+    /// Convert into an object which maps paths to reasons, for validation responses.
+    pub fn to_json(&self) -> Json {
+        let mut map = Map::new();
+        for e in &self.0 {
+            map.entry(e.path.clone())
+                .or_insert_with(|| json!(e.reason()));
+        }
+        Json::Object(map)
+    }
+}
+
+impl fmt::Display for JsonExtractErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, e) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            e.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for JsonExtractErrors {}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Trait for types which can be extracted from JSON values leniently,
+/// e.g. an integer from a string like [`de_x_num`](crate::serde::de_x_num).
+pub trait FromJsonValue: Sized {
+    /// Name of the expected type in errors.
+    const EXPECTED: &'static str;
+
+    /// Convert a JSON value at the path.
+    fn from_json_value(jsn: &Json, path: &mut JsonPath) -> Result<Self, JsonExtractError>;
+
+    /// Get the value if it's missing, only `Option` accepts a missing value.
+    fn from_missing() -> Option<Self> {
+        None
+    }
+}
+
+macro_rules! impl_from_json_value {
+    ($expected:literal, $de:path, $($T:ty),+) => {
+        $(
+            impl FromJsonValue for $T {
+                const EXPECTED: &'static str = $expected;
+
+                fn from_json_value(jsn: &Json, path: &mut JsonPath) -> Result<Self, JsonExtractError> {
+                    $de(jsn).map_err(|_| JsonExtractError::mismatch(path, Self::EXPECTED, jsn))
+                }
+            }
+        )+
+    };
+}
+
+#[cfg(feature = "num")]
+impl_from_json_value!(
+    "integer",
+    crate::serde::de_x_num,
+    i8,
+    i16,
+    i32,
+    i64,
+    isize,
+    u8,
+    u16,
+    u32,
+    u64,
+    usize
+);
+#[cfg(feature = "num")]
+impl_from_json_value!("number", crate::serde::de_x_float, f32, f64);
+impl_from_json_value!("boolean", crate::serde::de_x_bool, bool);
+#[cfg(feature = "datetime")]
+impl_from_json_value!(
+    "date-time",
+    crate::datetime::de_x_utc,
+    crate::datetime::DateTimeUtc
+);
+
+impl FromJsonValue for String {
+    const EXPECTED: &'static str = "string";
+
+    fn from_json_value(jsn: &Json, path: &mut JsonPath) -> Result<Self, JsonExtractError> {
+        match jsn {
+            Json::String(s) => Ok(s.clone()),
+            Json::Number(n) => Ok(n.to_string()),
+            Json::Bool(b) => Ok(b.to_string()),
+            _ => Err(JsonExtractError::mismatch(path, Self::EXPECTED, jsn)),
+        }
+    }
+}
+
+impl FromJsonValue for Json {
+    const EXPECTED: &'static str = "any";
+
+    #[inline]
+    fn from_json_value(jsn: &Json, _path: &mut JsonPath) -> Result<Self, JsonExtractError> {
+        Ok(jsn.clone())
+    }
+}
+
+/// Null or a missing value is `None`.
+impl<T: FromJsonValue> FromJsonValue for Option<T> {
+    const EXPECTED: &'static str = T::EXPECTED;
+
+    fn from_json_value(jsn: &Json, path: &mut JsonPath) -> Result<Self, JsonExtractError> {
+        match jsn {
+            Json::Null => Ok(None),
+            _ => T::from_json_value(jsn, path).map(Some),
+        }
+    }
+
+    #[inline]
+    fn from_missing() -> Option<Self> {
+        Some(None)
+    }
+}
+
+/// An error of an item reports the path of the item.
+impl<T: FromJsonValue> FromJsonValue for Vec<T> {
+    const EXPECTED: &'static str = "array";
+
+    fn from_json_value(jsn: &Json, path: &mut JsonPath) -> Result<Self, JsonExtractError> {
+        let Json::Array(array) = jsn else {
+            return Err(JsonExtractError::mismatch(path, Self::EXPECTED, jsn));
+        };
+        let mut v = Vec::with_capacity(array.len());
+        for (i, x) in array.iter().enumerate() {
+            path.push_index(i);
+            v.push(T::from_json_value(x, path)?);
+            path.pop();
+        }
+        Ok(v)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Trait to extract typed values from a JSON value by paths.
+pub trait JsonExtract {
+    /// Extract a value by a dotted path or a JSON Pointer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use xelf::json::*;
+    ///
+    /// let jsn = json!({"a": {"b": "42", "c": [true, "x"]}});
+    /// assert_eq!(jsn.extract::<i64>("a.b"), Ok(42));
+    /// assert_eq!(jsn.extract::<Option<i64>>("a.x"), Ok(None));
+    ///
+    /// let err = jsn.extract::<Vec<bool>>("a.c").unwrap_err();
+    /// assert_eq!(err.to_string(), "a.c[1]: expected boolean, found string");
+    /// ```
+    fn extract<T: FromJsonValue>(&self, path: &str) -> Result<T, JsonExtractError> {
+        match JsonPath::parse(path) {
+            Ok(path) => self.extract_at(&path),
+            Err(_) => Err(JsonExtractError {
+                path: path.to_owned(),
+                kind: JsonExtractErrorKind::InvalidPath,
+            }),
+        }
+    }
+
+    /// Extract a value by a parsed path.
+    fn extract_at<T: FromJsonValue>(&self, path: &JsonPath) -> Result<T, JsonExtractError>;
+
+    /// Create a builder to extract fields and collect all errors.
+    #[inline]
+    fn extractor(&self) -> JsonExtractor<'_, Self> {
+        JsonExtractor {
+            source: self,
+            errors: Vec::new(),
+        }
+    }
+}
+
+fn extract_member<T: FromJsonValue>(
+    jsn: Option<&Json>,
+    path: &JsonPath,
+) -> Result<T, JsonExtractError> {
+    match jsn {
+        Some(v) => T::from_json_value(v, &mut path.clone()),
+        None => T::from_missing().ok_or_else(|| JsonExtractError {
+            path: path.to_string(),
+            kind: JsonExtractErrorKind::Missing,
+        }),
+    }
+}
+
+impl JsonExtract for Json {
+    #[inline]
+    fn extract_at<T: FromJsonValue>(&self, path: &JsonPath) -> Result<T, JsonExtractError> {
+        extract_member(self.get_member(path), path)
+    }
+}
+
+impl JsonExtract for Map<String, Json> {
+    #[inline]
+    fn extract_at<T: FromJsonValue>(&self, path: &JsonPath) -> Result<T, JsonExtractError> {
+        extract_member(self.get_member(path), path)
+    }
+}
+
+/// Builder to extract fields and collect errors of all fields.
+///
+/// # Examples
+///
+/// ```
+/// use serde_json::json;
+/// use xelf::json::*;
+///
+/// let jsn = json!({"id": "7", "age": "x", "tags": ["a", 1]});
+/// let mut ex = jsn.extractor();
+/// let id: i64 = ex.field("id");
+/// let _age: u8 = ex.field("age");
+/// let _name: String = ex.field("name");
+/// let tags: Vec<String> = ex.field("tags");
+/// let nickname: Option<String> = ex.field("nickname");
+/// assert_eq!((id, tags, nickname), (7, vec!["a".to_owned(), "1".to_owned()], None));
+///
+/// let errors = ex.finish().unwrap_err();
+/// assert_eq!(
+///     errors.to_json(),
+///     json!({"age": "expected integer, found string", "name": "missing value"})
+/// );
+/// ```
+pub struct JsonExtractor<'a, V: ?Sized> {
+    source: &'a V,
+    errors: Vec<JsonExtractError>,
+}
+
+impl<V: ?Sized + JsonExtract> JsonExtractor<'_, V> {
+    /// Extract a field, returns the default value and records the error on failure.
+    pub fn field<T: FromJsonValue + Default>(&mut self, path: &str) -> T {
+        self.field_or_else(path, T::default)
+    }
+
+    /// Extract a field, returns `default` and records the error on failure.
+    #[inline]
+    pub fn field_or<T: FromJsonValue>(&mut self, path: &str, default: T) -> T {
+        self.field_or_else(path, || default)
+    }
+
+    /// Extract a field, returns the result of `f` and records the error on failure.
+    pub fn field_or_else<T, F>(&mut self, path: &str, f: F) -> T
+    where
+        T: FromJsonValue,
+        F: FnOnce() -> T,
+    {
+        match self.source.extract(path) {
+            Ok(v) => v,
+            Err(e) => {
+                self.errors.push(e);
+                f()
+            }
+        }
+    }
+
+    /// Record a custom error of a field, e.g. from a semantic check.
+    pub fn error(&mut self, error: JsonExtractError) {
+        self.errors.push(error);
+    }
+
+    /// Get the errors collected so far.
+    #[inline]
+    pub fn errors(&self) -> &[JsonExtractError] {
+        &self.errors
+    }
+
+    /// Finish extraction, fail if any field failed.
+    pub fn finish(self) -> Result<(), JsonExtractErrors> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(JsonExtractErrors(self.errors))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_extract() {
+        let jsn = json!({
+            "a": {"n": "-3", "f": "1.5", "b": 1, "s": 2, "null": null},
+            "list": [[1, "2"], [3, {}]],
+            "time": "2022-01-01T00:00:00Z",
+        });
+        assert_eq!(jsn.extract::<i32>("a.n"), Ok(-3));
+        assert_eq!(jsn.extract::<f64>("/a/f"), Ok(1.5));
+        assert_eq!(jsn.extract::<bool>("a.b"), Ok(true));
+        assert_eq!(jsn.extract::<String>("a.s"), Ok("2".to_owned()));
+        assert_eq!(jsn.extract::<Option<String>>("a.null"), Ok(None));
+        assert_eq!(jsn.extract::<Json>("a.b"), Ok(json!(1)));
+        assert_eq!(jsn.extract::<Vec<u8>>("list[0]"), Ok(vec![1, 2]));
+        #[cfg(feature = "datetime")]
+        assert_eq!(
+            jsn.extract::<crate::datetime::DateTimeUtc>("time")
+                .unwrap()
+                .timestamp(),
+            1_640_995_200
+        );
+
+        let err = jsn.extract::<u32>("a.n").unwrap_err();
+        assert_eq!(
+            err,
+            JsonExtractError {
+                path: "a.n".to_owned(),
+                kind: JsonExtractErrorKind::Mismatch {
+                    expected: "integer",
+                    actual: JsonType::String
+                }
+            }
+        );
+        let err = jsn.extract::<Vec<Vec<i64>>>("list").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "list[1][1]: expected integer, found object"
+        );
+        let err = jsn.extract::<String>("a.null").unwrap_err();
+        assert_eq!(err.to_string(), "a.null: expected string, found null");
+        assert_eq!(
+            jsn.extract::<i64>("a.x").unwrap_err().kind,
+            JsonExtractErrorKind::Missing
+        );
+        assert_eq!(
+            jsn.extract::<i64>("a[").unwrap_err().kind,
+            JsonExtractErrorKind::InvalidPath
+        );
+        let map = jsn.as_object().unwrap();
+        assert_eq!(map.extract::<i64>("a.n"), Ok(-3));
+
+        let mut ex = map.extractor();
+        assert_eq!(ex.field_or("a.f", 0), 0);
+        assert_eq!(ex.field_or("a.x", 2.5), 2.5);
+        assert_eq!(ex.errors().len(), 2);
+        let errors = ex.finish().unwrap_err();
+        assert_eq!(
+            errors.to_string(),
+            "a.f: expected integer, found string; a.x: missing value"
+        );
+        assert!(jsn.extractor().finish().is_ok());
+    }
+}
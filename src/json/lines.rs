@@ -0,0 +1,547 @@
+//! JSON Lines (NDJSON) readers and writers, synchronous and asynchronous.
+//!
+//! Blank lines are skipped. A line which can not be parsed, including invalid UTF-8,
+//! is reported as an error item with its line number, and reading continues with the next line.
+
+use ::serde::{de::DeserializeOwned, ser::Serialize};
+use ::serde_json::Value as Json;
+use ::std::{
+    fmt,
+    io::{self, BufRead, Write},
+    marker::PhantomData,
+};
+
+/// Error of reading JSON Lines.
+#[derive(Debug)]
+pub enum JsonLinesError {
+    /// An I/O error, which ends the reading.
+    Io(io::Error),
+    /// A line can not be parsed.
+    Parse {
+        line: usize,
+        error: serde_json::Error,
+    },
+    /// A line exceeds the maximum length.
+    TooLong { line: usize },
+}
+
+impl JsonLinesError {
+    /// Get the 1-based line number of a line error.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Self::Io(_) => None,
+            Self::Parse { line, .. } | Self::TooLong { line } => Some(*line),
+        }
+    }
+}
+
+impl fmt::Display for JsonLinesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "I/O error: {}", e),
+            Self::Parse { line, error } => write!(f, "line {}: {}", line, error),
+            Self::TooLong { line } => write!(f, "line {}: exceeds the maximum length", line),
+        }
+    }
+}
+
+impl std::error::Error for JsonLinesError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Parse { error, .. } => Some(error),
+            Self::TooLong { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for JsonLinesError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// Parse a line, `None` if it's blank.
+fn parse_line<T: DeserializeOwned>(
+    line: &[u8],
+    number: usize,
+) -> Option<Result<T, JsonLinesError>> {
+    let line = line
+        .strip_prefix(b"\xef\xbb\xbf")
+        .unwrap_or(line)
+        .trim_ascii();
+    if line.is_empty() {
+        return None;
+    }
+    Some(
+        serde_json::from_slice(line).map_err(|error| JsonLinesError::Parse {
+            line: number,
+            error,
+        }),
+    )
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Iterator of values of type `T` read from JSON Lines.
+///
+/// # Examples
+///
+/// ```
+/// use serde_json::Value as Json;
+/// use xelf::json::*;
+///
+/// let input = b"{\"id\": 1}\n\nnot json\n{\"id\": 3}\n";
+/// let items: Vec<_> = JsonLinesReader::<_, Json>::new(&input[..]).collect();
+/// assert_eq!(items.len(), 3);
+/// assert_eq!(items[1].as_ref().unwrap_err().line(), Some(3));
+/// assert_eq!(items[2].as_ref().unwrap()["id"], 3);
+/// ```
+pub struct JsonLinesReader<R, T = Json> {
+    reader: R,
+    line: usize,
+    buf: Vec<u8>,
+    max_length: usize,
+    done: bool,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<R: BufRead, T: DeserializeOwned> JsonLinesReader<R, T> {
+    pub fn new(reader: R) -> Self {
+        Self::with_max_length(reader, usize::MAX)
+    }
+
+    /// Create a reader that reports lines longer than `max_length` bytes as errors,
+    /// without buffering them.
+    pub fn with_max_length(reader: R, max_length: usize) -> Self {
+        Self {
+            reader,
+            line: 0,
+            buf: Vec::new(),
+            max_length,
+            done: false,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Get the number of lines read.
+    #[inline]
+    pub fn line_number(&self) -> usize {
+        self.line
+    }
+
+    /// Consumes this reader, returning the underlying reader.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Read a line into the buffer without the newline, returns `None` at the end,
+    /// or whether the line is too long, whose bytes are discarded.
+    fn read_line(&mut self) -> io::Result<Option<bool>> {
+        self.buf.clear();
+        let (mut read, mut too_long) = (false, false);
+        loop {
+            let available = match self.reader.fill_buf() {
+                Ok(v) => v,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            if available.is_empty() {
+                return Ok(read.then_some(too_long));
+            }
+            read = true;
+            let (chunk, used, end) = match available.iter().position(|&c| c == b'\n') {
+                Some(i) => (&available[..i], i + 1, true),
+                None => (available, available.len(), false),
+            };
+            if !too_long {
+                if self.buf.len() + chunk.len() > self.max_length {
+                    too_long = true;
+                    self.buf.clear();
+                } else {
+                    self.buf.extend_from_slice(chunk);
+                }
+            }
+            self.reader.consume(used);
+            if end {
+                return Ok(Some(too_long));
+            }
+        }
+    }
+}
+
+impl<R: BufRead, T: DeserializeOwned> Iterator for JsonLinesReader<R, T> {
+    type Item = Result<T, JsonLinesError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            match self.read_line() {
+                Ok(None) => self.done = true,
+                Ok(Some(true)) => {
+                    self.line += 1;
+                    return Some(Err(JsonLinesError::TooLong { line: self.line }));
+                }
+                Ok(Some(false)) => {
+                    self.line += 1;
+                    if let Some(item) = parse_line(&self.buf, self.line) {
+                        return Some(item);
+                    }
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e.into()));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Writer of values as JSON Lines.
+pub struct JsonLinesWriter<W> {
+    writer: W,
+}
+
+impl<W: Write> JsonLinesWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Write a value in a line.
+    pub fn write<T: ?Sized + Serialize>(&mut self, value: &T) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, value)?;
+        self.writer.write_all(b"\n")
+    }
+
+    /// Write all values of an iterator, each in a line.
+    pub fn write_all<I>(&mut self, values: I) -> io::Result<()>
+    where
+        I: IntoIterator,
+        I::Item: Serialize,
+    {
+        values.into_iter().try_for_each(|x| self.write(&x))
+    }
+
+    #[inline]
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Acquires a reference to the underlying writer.
+    #[inline]
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Consumes this writer, returning the underlying writer.
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "future")]
+pub use self::codec::*;
+
+#[cfg(feature = "future")]
+mod codec {
+    use super::*;
+    use crate::future::DuplexStream;
+    use futures::Stream;
+    use pin_project::pin_project;
+    use std::{
+        pin::Pin,
+        task::{Context, Poll},
+    };
+    use tokio::io::{AsyncRead, AsyncWrite};
+    use tokio_util::{
+        bytes::{BufMut, Bytes, BytesMut},
+        codec::{
+            AnyDelimiterCodec, AnyDelimiterCodecError, Decoder, Encoder, FramedRead, FramedWrite,
+        },
+    };
+
+    /// Codec of JSON Lines, which decodes values of type `T` and encodes any serializable values.
+    ///
+    /// A decoded item is a line result, so that a bad line does not end a framed stream.
+    pub struct JsonLinesCodec<T = Json> {
+        lines: AnyDelimiterCodec,
+        line: usize,
+        _phantom: PhantomData<fn() -> T>,
+    }
+
+    impl<T> Default for JsonLinesCodec<T> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl<T> JsonLinesCodec<T> {
+        pub fn new() -> Self {
+            Self::with_max_length(usize::MAX)
+        }
+
+        /// Create a codec that reports lines longer than `max_length` bytes as errors.
+        pub fn with_max_length(max_length: usize) -> Self {
+            Self {
+                // Lines are split as bytes, so that invalid UTF-8 is an error of the line.
+                lines: AnyDelimiterCodec::new_with_max_length(
+                    b"\n".to_vec(),
+                    b"\n".to_vec(),
+                    max_length,
+                ),
+                line: 0,
+                _phantom: PhantomData,
+            }
+        }
+
+        /// Get the number of lines decoded.
+        #[inline]
+        pub fn line_number(&self) -> usize {
+            self.line
+        }
+    }
+
+    impl<T: DeserializeOwned> JsonLinesCodec<T> {
+        fn decode_with<F>(&mut self, buf: &mut BytesMut, f: F) -> DecodeResult<T>
+        where
+            F: Fn(
+                &mut AnyDelimiterCodec,
+                &mut BytesMut,
+            ) -> Result<Option<Bytes>, AnyDelimiterCodecError>,
+        {
+            loop {
+                match f(&mut self.lines, buf) {
+                    Ok(Some(s)) => {
+                        self.line += 1;
+                        if let Some(item) = parse_line(&s, self.line) {
+                            return Ok(Some(item));
+                        }
+                    }
+                    Ok(None) => return Ok(None),
+                    Err(AnyDelimiterCodecError::MaxChunkLengthExceeded) => {
+                        self.line += 1;
+                        return Ok(Some(Err(JsonLinesError::TooLong { line: self.line })));
+                    }
+                    Err(AnyDelimiterCodecError::Io(e)) => return Err(e.into()),
+                }
+            }
+        }
+    }
+
+    type DecodeResult<T> = Result<Option<Result<T, JsonLinesError>>, JsonLinesError>;
+
+    impl<T: DeserializeOwned> Decoder for JsonLinesCodec<T> {
+        type Item = Result<T, JsonLinesError>;
+        type Error = JsonLinesError;
+
+        fn decode(&mut self, buf: &mut BytesMut) -> DecodeResult<T> {
+            self.decode_with(buf, AnyDelimiterCodec::decode)
+        }
+
+        fn decode_eof(&mut self, buf: &mut BytesMut) -> DecodeResult<T> {
+            self.decode_with(buf, AnyDelimiterCodec::decode_eof)
+        }
+    }
+
+    impl<T, U: Serialize> Encoder<U> for JsonLinesCodec<T> {
+        type Error = io::Error;
+
+        fn encode(&mut self, item: U, dst: &mut BytesMut) -> io::Result<()> {
+            serde_json::to_writer(dst.writer(), &item)?;
+            dst.put_u8(b'\n');
+            Ok(())
+        }
+    }
+
+    /// Stream of values of type `T` read from JSON Lines, see [`JsonLinesReader`].
+    #[pin_project]
+    pub struct JsonLinesStream<R, T = Json> {
+        #[pin]
+        inner: FramedRead<R, JsonLinesCodec<T>>,
+    }
+
+    impl<R: AsyncRead, T: DeserializeOwned> JsonLinesStream<R, T> {
+        pub fn new(reader: R) -> Self {
+            Self::with_codec(reader, JsonLinesCodec::new())
+        }
+
+        pub fn with_codec(reader: R, codec: JsonLinesCodec<T>) -> Self {
+            Self {
+                inner: FramedRead::new(reader, codec),
+            }
+        }
+
+        /// Get the number of lines read.
+        #[inline]
+        pub fn line_number(&self) -> usize {
+            self.inner.decoder().line_number()
+        }
+
+        crate::future_delegate_access_inner!(inner, R, (.));
+    }
+
+    impl<R: AsyncRead, T: DeserializeOwned> Stream for JsonLinesStream<R, T> {
+        type Item = Result<T, JsonLinesError>;
+
+        fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            self.project()
+                .inner
+                .poll_next(cx)
+                .map(|x| x.map(|x| x.and_then(|x| x)))
+        }
+    }
+
+    /// Sink of values written as JSON Lines.
+    pub type JsonLinesSink<W> = FramedWrite<W, JsonLinesCodec>;
+
+    /// Create a sink of values written as JSON Lines.
+    pub fn json_lines_sink<W: AsyncWrite>(writer: W) -> JsonLinesSink<W> {
+        FramedWrite::new(writer, JsonLinesCodec::new())
+    }
+
+    /// Duplex of a JSON Lines stream of `T` and a JSON Lines sink of `U`.
+    pub type JsonLinesDuplex<R, W, T, U = T> =
+        DuplexStream<JsonLinesSink<W>, U, JsonLinesStream<R, T>>;
+
+    /// Create a duplex which reads `T` from `reader` and writes `U` into `writer`.
+    pub fn json_lines_duplex<R, W, T, U>(reader: R, writer: W) -> JsonLinesDuplex<R, W, T, U>
+    where
+        R: AsyncRead,
+        W: AsyncWrite,
+        T: DeserializeOwned,
+        U: Serialize,
+    {
+        DuplexStream::new(json_lines_sink(writer), JsonLinesStream::new(reader))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Row {
+        id: i64,
+        name: String,
+    }
+
+    #[test]
+    fn test_json_lines() {
+        let rows = [
+            Row {
+                id: 1,
+                name: "a".to_owned(),
+            },
+            Row {
+                id: 2,
+                name: "b".to_owned(),
+            },
+        ];
+        let mut writer = JsonLinesWriter::new(Vec::new());
+        writer.write_all(&rows).unwrap();
+        writer.write(&serde_json::json!({"id": "x"})).unwrap();
+        let mut buf = writer.into_inner();
+        assert_eq!(
+            String::from_utf8_lossy(&buf),
+            "{\"id\":1,\"name\":\"a\"}\n{\"id\":2,\"name\":\"b\"}\n{\"id\":\"x\"}\n"
+        );
+        buf.extend_from_slice(b"\r\n  \n{\"id\":3,\"name\":\"c\"}");
+
+        let mut reader = JsonLinesReader::<_, Row>::new(&buf[..]);
+        assert_eq!(reader.next().unwrap().unwrap(), rows[0]);
+        assert_eq!(reader.next().unwrap().unwrap(), rows[1]);
+        let err = reader.next().unwrap().unwrap_err();
+        assert!(matches!(err, JsonLinesError::Parse { line: 3, .. }));
+        assert_eq!(reader.next().unwrap().unwrap().id, 3);
+        assert!(reader.next().is_none());
+        assert_eq!(reader.line_number(), 6);
+
+        // Invalid UTF-8 and long lines are errors of their lines.
+        let input = b"{\"id\":1}\n\"\xff\"\n[1,2,3,4,5]\n{}\n";
+        let items: Vec<_> = JsonLinesReader::<_, Json>::with_max_length(&input[..], 8).collect();
+        assert_eq!(items.len(), 4);
+        assert_eq!(items[0].as_ref().unwrap()["id"], 1);
+        assert!(matches!(
+            items[1],
+            Err(JsonLinesError::Parse { line: 2, .. })
+        ));
+        assert!(matches!(items[2], Err(JsonLinesError::TooLong { line: 3 })));
+        assert_eq!(items[3].as_ref().unwrap(), &serde_json::json!({}));
+        // A long line is discarded across the buffer.
+        let reader = std::io::BufReader::with_capacity(2, &b"[1,2,3,4,5]\n{}"[..]);
+        let items: Vec<_> = JsonLinesReader::<_, Json>::with_max_length(reader, 8).collect();
+        assert!(matches!(items[0], Err(JsonLinesError::TooLong { line: 1 })));
+        assert_eq!(items[1].as_ref().unwrap(), &serde_json::json!({}));
+    }
+
+    #[cfg(feature = "future")]
+    #[tokio::test]
+    async fn test_json_lines_async() {
+        use crate::future::SinkXlf;
+        use futures::{SinkExt, StreamExt};
+
+        let input = b"{\"id\":1,\"name\":\"a\"}\n\n[1]\n{\"id\":12345678901234567890,\"name\":\"b\"}\n{\"id\":2,\"name\":\"b\"}";
+        let stream = JsonLinesStream::<_, Row>::new(&input[..]);
+        let items: Vec<_> = stream.collect().await;
+        assert_eq!(items.len(), 4);
+        assert_eq!(items[0].as_ref().unwrap().id, 1);
+        assert_eq!(items[1].as_ref().unwrap_err().line(), Some(3));
+        assert_eq!(items[2].as_ref().unwrap_err().line(), Some(4));
+        assert_eq!(items[3].as_ref().unwrap().id, 2);
+
+        let codec = JsonLinesCodec::<Json>::with_max_length(8);
+        let mut stream = JsonLinesStream::with_codec(&b"[1,2,3,4,5]\n{}\n"[..], codec);
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert!(matches!(err, JsonLinesError::TooLong { line: 1 }));
+        assert_eq!(stream.next().await.unwrap().unwrap(), serde_json::json!({}));
+        assert!(stream.next().await.is_none());
+
+        let mut stream = JsonLinesStream::<_, Json>::new(&b"\"\xff\"\n[1]\r\n"[..]);
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert!(matches!(err, JsonLinesError::Parse { line: 1, .. }));
+        assert_eq!(
+            stream.next().await.unwrap().unwrap(),
+            serde_json::json!([1])
+        );
+        assert!(stream.next().await.is_none());
+
+        // The sink accepts any serializable item, so the item type of `SinkXlf` is explicit.
+        let mut sink =
+            SinkXlf::<Json>::safe_sink_map_err(json_lines_sink(Vec::new()), |e| e.to_string());
+        sink.send(Row {
+            id: 1,
+            name: "a".to_owned(),
+        })
+        .await
+        .unwrap();
+        sink.send(serde_json::json!([1])).await.unwrap();
+        assert_eq!(
+            sink.get_ref().get_ref(),
+            b"{\"id\":1,\"name\":\"a\"}\n[1]\n"
+        );
+
+        let (client, server) = tokio::io::duplex(1024);
+        let (reader, writer) = tokio::io::split(client);
+        let mut duplex = json_lines_duplex::<_, _, Row, Row>(reader, writer);
+        let (server_reader, mut server_writer) = tokio::io::split(server);
+        duplex
+            .send(Row {
+                id: 7,
+                name: "x".to_owned(),
+            })
+            .await
+            .unwrap();
+        let mut server_stream = JsonLinesStream::<_, Json>::new(server_reader);
+        let jsn = server_stream.next().await.unwrap().unwrap();
+        assert_eq!(jsn["id"], 7);
+        tokio::io::AsyncWriteExt::write_all(&mut server_writer, b"{\"id\":8,\"name\":\"y\"}\n")
+            .await
+            .unwrap();
+        assert_eq!(duplex.next().await.unwrap().unwrap().id, 8);
+    }
+}
@@ -0,0 +1,703 @@
+mod canonical;
+mod case;
+mod config;
+mod extract;
+mod lines;
+mod patch;
+mod path;
+mod project;
+mod schema;
+mod update;
+
+pub use canonical::to_canonical_writer;
+pub use case::*;
+pub use config::*;
+pub use extract::*;
+pub use lines::*;
+pub use patch::*;
+pub use path::*;
+pub use schema::*;
+
+use crate::collections::Contains;
+use ::serde::{de::DeserializeOwned, ser::Serialize};
+use ::serde_json::{json, map::Map, value::Index, Value as Json};
+use ::std::{borrow::Borrow, hash::Hash};
+#[cfg(feature = "num")]
+use num_traits::{AsPrimitive, Float, FromPrimitive, PrimInt};
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Trait for JSON object, arrary, associated with an index.
+pub trait JsonIndexed<I> {
+    fn get_member(&self, index: I) -> Option<&Json>;
+}
+
+impl<I: Index> JsonIndexed<I> for Json {
+    #[inline(always)]
+    fn get_member(&self, index: I) -> Option<&Json> {
+        self.get(index)
+    }
+}
+
+impl<I: AsRef<str>> JsonIndexed<I> for Map<String, Json> {
+    #[inline(always)]
+    fn get_member(&self, index: I) -> Option<&Json> {
+        self.get(index.as_ref())
+    }
+}
+
+impl<I: PrimInt + AsPrimitive<usize> + Index> JsonIndexed<I> for Vec<Json> {
+    #[inline(always)]
+    fn get_member(&self, index: I) -> Option<&Json> {
+        self.get(index.as_())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Trait to get a field value with a default value.
+pub trait JsonGetOr<'a, I, T, _T> {
+    /// Get a field value, returns the default value if failed.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - A string (slice) for a child value, an integer value of an array item,
+    ///   or a [`JsonPath`] for a nested value.
+    ///
+    /// * `default` - The default value returned on error.
+    ///
+    /// # Returns
+    ///
+    /// If there is an item which matches the index and the default value's type, return its value.
+    ///
+    /// Otherwize, returns the default value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use xelf::json::*;
+    ///
+    /// let jsn = json!({"name": "Tom", "value": 100});
+    ///
+    /// assert_eq!(jsn.get_or("name", "John"), "Tom");
+    /// // type dese not match
+    /// assert_eq!(jsn.get_or("name", 1), 1);
+    /// // index dese not match
+    /// assert_eq!(jsn.get_or("Name", "Json"), "Json");
+    ///
+    /// assert_eq!(jsn.get_or("value", 1), 100);
+    /// // type dese not match
+    /// assert_eq!(jsn.get_or("value", "1"), "1");
+    /// ```
+    fn get_or(&'a self, index: I, default: T) -> T;
+
+    /// Get a field value, returns the default value if failed.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - A string (slice) for a child value, an integer value of an array item,
+    ///   or a [`JsonPath`] for a nested value.
+    ///
+    /// * `f` - A function to return the default value.
+    ///
+    /// # Returns
+    ///
+    /// If there is an item which matches the index and the default value's type, return its value.
+    ///
+    /// Otherwize, call the function and returns it's result.
+    fn get_or_else<F: FnOnce() -> T>(&'a self, index: I, f: F) -> T;
+}
+
+#[cfg(feature = "num")]
+impl<I, T, V> JsonGetOr<'_, I, T, i64> for V
+where
+    T: PrimInt + FromPrimitive,
+    V: JsonIndexed<I>,
+{
+    #[inline]
+    fn get_or(&self, index: I, default: T) -> T {
+        self.get_member(index)
+            .and_then(|x| x.as_i64())
+            .and_then(|x| T::from_i64(x))
+            .unwrap_or(default)
+    }
+
+    #[inline]
+    fn get_or_else<F: FnOnce() -> T>(&self, index: I, f: F) -> T {
+        self.get_member(index)
+            .and_then(|x| x.as_i64())
+            .and_then(|x| T::from_i64(x))
+            .unwrap_or_else(f)
+    }
+}
+
+#[cfg(feature = "num")]
+impl<I, T, V> JsonGetOr<'_, I, T, f64> for V
+where
+    T: Float + FromPrimitive,
+    V: JsonIndexed<I>,
+{
+    #[inline]
+    fn get_or(&self, index: I, default: T) -> T {
+        self.get_member(index)
+            .and_then(|x| x.as_f64())
+            .and_then(|x| T::from_f64(x))
+            .unwrap_or(default)
+    }
+
+    #[inline]
+    fn get_or_else<F: FnOnce() -> T>(&self, index: I, f: F) -> T {
+        self.get_member(index)
+            .and_then(|x| x.as_f64())
+            .and_then(|x| T::from_f64(x))
+            .unwrap_or_else(f)
+    }
+}
+
+impl<'a, I, V: JsonIndexed<I>> JsonGetOr<'a, I, &'a str, char> for V {
+    #[inline]
+    fn get_or(&'a self, index: I, default: &'a str) -> &'a str {
+        self.get_member(index)
+            .and_then(|x| x.as_str())
+            .unwrap_or(default)
+    }
+
+    #[inline]
+    fn get_or_else<F: FnOnce() -> &'a str>(&'a self, index: I, f: F) -> &'a str {
+        self.get_member(index)
+            .and_then(|x| x.as_str())
+            .unwrap_or_else(f)
+    }
+}
+
+impl<I, V: JsonIndexed<I>> JsonGetOr<'_, I, bool, bool> for V {
+    #[inline]
+    fn get_or(&self, index: I, default: bool) -> bool {
+        self.get_member(index)
+            .and_then(|x| x.as_bool())
+            .unwrap_or(default)
+    }
+
+    #[inline]
+    fn get_or_else<F: FnOnce() -> bool>(&self, index: I, f: F) -> bool {
+        self.get_member(index)
+            .and_then(|x| x.as_bool())
+            .unwrap_or_else(f)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Extension for serde_json::Value.
+pub trait JsonObjectXlf {
+    /// Insert a key-value pair into a JSON object by specifying the key name
+    /// and the value to be assigned to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `k` `v`: can be primitive data type, such as string references or integers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use xelf::json::*;
+    ///
+    /// let mut jsn = json!({});
+    ///
+    /// jsn.insert_s("name", "tom");
+    /// jsn.insert_s("age", 16);
+    ///
+    /// assert_eq!(jsn.get_or("name", ""), "tom");
+    /// assert_eq!(jsn.get_or("age", 16), 16);
+    /// ```
+    fn insert_s<T: Serialize>(&mut self, k: &str, v: T) -> Option<Json>;
+
+    /// Collect all fields that have the specified prefix and insert them into
+    /// a new object, removing the prefix from the field names before insertion.
+    ///
+    fn take_with_prefix(&mut self, prefix: &str) -> Self;
+
+    /// Merge this JSON object to a serializable object, skip the fields in `skip`.
+    fn merge_to<T, S, K>(&self, dst: &mut T, skip: &S) -> serde_json::Result<()>
+    where
+        T: Serialize + DeserializeOwned,
+        S: ?Sized + Contains<K, str>,
+        K: Hash + Ord + Eq + Borrow<str>,
+    {
+        self.merge_to_with(dst, skip, KeyMatch::Exact)
+    }
+
+    /// Merge this JSON object to a serializable object, skip the fields in `skip`,
+    /// the keys are matched to the field names by `key_match`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use xelf::json::*;
+    ///
+    /// let mut dst = json!({"user_name": "", "age": 0});
+    /// json!({"userName": "tom", "age": 16})
+    ///     .merge_to_with(&mut dst, &None::<&str>, KeyMatch::Case(KeyCase::Snake))
+    ///     .unwrap();
+    /// assert_eq!(dst, json!({"user_name": "tom", "age": 16}));
+    /// ```
+    fn merge_to_with<T, S, K>(
+        &self,
+        dst: &mut T,
+        skip: &S,
+        key_match: KeyMatch,
+    ) -> serde_json::Result<()>
+    where
+        T: Serialize + DeserializeOwned,
+        S: ?Sized + Contains<K, str>,
+        K: Hash + Ord + Eq + Borrow<str>;
+
+    /// Recursively rename keys of all nested objects into a case.
+    fn rename_keys(&mut self, case: KeyCase) {
+        self.rename_keys_with(|k| case.convert(k))
+    }
+
+    /// Recursively rename keys of all nested objects by a function,
+    /// a renamed key overwrites an existing one.
+    fn rename_keys_with<F: FnMut(&str) -> String>(&mut self, f: F);
+
+    /// Keep only the fields matched by dotted path patterns in `fields`, e.g. `profile.bio`,
+    /// where `*` matches any single key at any depth. Arrays are transparent, so `items.id` keeps
+    /// the `id` of all elements of `items`. An array keeps the projection of each element.
+    /// `\` escapes the next character, e.g. `a\.b` is the key `a.b` and `\*` is the key `*`.
+    ///
+    /// Unlike the `skip` set of merges, the patterns are any iterable of strings
+    /// rather than a [`Contains`] set, since wildcard patterns are listed to be parsed
+    /// and can't be looked up by a key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use xelf::json::*;
+    ///
+    /// let jsn = json!({"id": 1, "name": "tom", "profile": {"bio": "b", "token": "t"}});
+    /// assert_eq!(
+    ///     jsn.project(&["id", "profile.bio"]),
+    ///     json!({"id": 1, "profile": {"bio": "b"}})
+    /// );
+    /// ```
+    fn project<I>(&self, fields: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>;
+
+    /// Remove the fields matched by dotted path patterns in `patterns`,
+    /// with the same syntax as [`project`](JsonObjectXlf::project).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use xelf::json::*;
+    ///
+    /// let mut jsn = json!({"id": 1, "password_hash": "x", "salt": "y", "profile": {"token": "t"}});
+    /// jsn.redact(&["password_hash", "salt", "*.token"]);
+    /// assert_eq!(jsn, json!({"id": 1, "profile": {}}));
+    /// ```
+    fn redact<I>(&mut self, patterns: I)
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>;
+
+    /// Recursively update all fields of this JSON object with another JSON object.
+    ///
+    /// # Arguments
+    ///
+    /// * `source`: the source JSON object.
+    /// * `allow_null`: If set to `true`, the function allows updating target fields
+    ///   with null values from the source object.
+    ///   If set to `false`, it ignores null values from the source object.
+    ///
+    fn deep_update_with(&mut self, source: Json, allow_null: bool);
+
+    /// Get a nested value by a path.
+    fn get_path(&self, path: &JsonPath) -> Option<&Json>;
+
+    /// Get a mutable nested value by a path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use xelf::json::*;
+    ///
+    /// let mut jsn = json!({"a": [{"b": 1}]});
+    /// let path = JsonPath::parse("a[0].b").unwrap();
+    /// *jsn.get_path_mut(&path).unwrap() = json!(2);
+    /// assert_eq!(jsn, json!({"a": [{"b": 2}]}));
+    /// ```
+    fn get_path_mut(&mut self, path: &JsonPath) -> Option<&mut Json>;
+
+    /// Apply an RFC 7386 JSON Merge Patch, null values in the patch remove fields.
+    ///
+    /// A map ignores a patch which is not an object.
+    fn apply_merge_patch(&mut self, patch: Json);
+
+    /// Apply an RFC 6902 JSON Patch, all operations are applied or none of them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use xelf::json::*;
+    ///
+    /// let mut jsn = json!({"a": [1, 2]});
+    /// let patch = json!([
+    ///     {"op": "add", "path": "/a/-", "value": 3},
+    ///     {"op": "test", "path": "/b", "value": 0},
+    /// ]);
+    /// let err = jsn.apply_json_patch(&patch).unwrap_err();
+    /// assert_eq!((err.index, err.kind), (1, JsonPatchErrorKind::NotFound));
+    /// assert_eq!(jsn, json!({"a": [1, 2]}));
+    /// ```
+    fn apply_json_patch(&mut self, patch: &Json) -> Result<(), JsonPatchError>;
+
+    /// Generate an RFC 6902 JSON Patch which transforms this value into `target`.
+    fn diff_to_json_patch(&self, target: &Self) -> Json;
+
+    /// Serialize into the canonical form of RFC 8785, see [`to_canonical_writer`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use xelf::json::*;
+    ///
+    /// let jsn = json!({"b": [1.50, 1e21], "a": "x"});
+    /// assert_eq!(jsn.to_canonical_string(), r#"{"a":"x","b":[1.5,1e+21]}"#);
+    /// ```
+    fn to_canonical_string(&self) -> String {
+        // The canonical form is always valid UTF-8.
+        String::from_utf8(self.to_canonical_vec()).unwrap_or_default()
+    }
+
+    /// Serialize into the canonical form of RFC 8785 as bytes.
+    fn to_canonical_vec(&self) -> Vec<u8>;
+
+    /// Get the 64-bit FNV-1a hash of the canonical form, which is stable across
+    /// processes and versions, and independent of the order of object keys,
+    /// e.g. as a cache key of a request payload.
+    /// Integers which round to the same double have the same hash.
+    fn canonical_hash(&self) -> u64;
+}
+
+impl JsonObjectXlf for Json {
+    #[inline]
+    fn insert_s<T: Serialize>(&mut self, k: &str, v: T) -> Option<Json> {
+        self.as_object_mut().unwrap().insert(k.to_owned(), json!(v))
+    }
+
+    fn take_with_prefix(&mut self, prefix: &str) -> Self {
+        Json::from(if let Some(src) = self.as_object_mut() {
+            src.take_with_prefix(prefix)
+        } else {
+            Map::<String, Json>::new()
+        })
+    }
+
+    fn merge_to_with<T, S, K>(
+        &self,
+        dst: &mut T,
+        skip: &S,
+        key_match: KeyMatch,
+    ) -> serde_json::Result<()>
+    where
+        T: Serialize + DeserializeOwned,
+        S: ?Sized + Contains<K, str>,
+        K: Hash + Ord + Eq + Borrow<str>,
+    {
+        if let Some(map) = self.as_object() {
+            map.merge_to_with(dst, skip, key_match)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn rename_keys_with<F: FnMut(&str) -> String>(&mut self, mut f: F) {
+        case::rename_keys(self, &mut f);
+    }
+
+    fn project<I>(&self, fields: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        match self {
+            Json::Object(map) => Json::Object(map.project(fields)),
+            Json::Array(_) => {
+                let patterns = project::Patterns::new(fields);
+                project::project_value(self, &[&patterns]).unwrap_or_else(|| json!([]))
+            }
+            _ => Json::Null,
+        }
+    }
+
+    fn redact<I>(&mut self, patterns: I)
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let patterns = project::Patterns::new(patterns);
+        project::redact_value(self, &[&patterns]);
+    }
+
+    fn deep_update_with(&mut self, source: Json, allow_null: bool) {
+        let mut path = JsonPath::new();
+        update::deep_update_value(
+            self,
+            source,
+            allow_null,
+            KeyMatch::Exact,
+            &mut path,
+            &mut |_, _| (),
+        );
+    }
+
+    #[inline]
+    fn get_path(&self, path: &JsonPath) -> Option<&Json> {
+        path.get(self)
+    }
+
+    #[inline]
+    fn get_path_mut(&mut self, path: &JsonPath) -> Option<&mut Json> {
+        path.get_mut(self)
+    }
+
+    #[inline]
+    fn apply_merge_patch(&mut self, patch: Json) {
+        patch::apply_merge_patch(self, patch)
+    }
+
+    #[inline]
+    fn apply_json_patch(&mut self, patch: &Json) -> Result<(), JsonPatchError> {
+        patch::apply_json_patch(self, patch)
+    }
+
+    #[inline]
+    fn diff_to_json_patch(&self, target: &Self) -> Json {
+        patch::diff_to_json_patch(self, target)
+    }
+
+    fn to_canonical_vec(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        let _ = to_canonical_writer(&mut buf, self);
+        buf
+    }
+
+    fn canonical_hash(&self) -> u64 {
+        let mut hasher = canonical::Fnv1aWriter::default();
+        let _ = to_canonical_writer(&mut hasher, self);
+        hasher.0
+    }
+}
+
+impl JsonObjectXlf for Map<String, Json> {
+    #[inline]
+    fn insert_s<T: Serialize>(&mut self, k: &str, v: T) -> Option<Json> {
+        self.insert(k.to_owned(), json!(v))
+    }
+
+    fn take_with_prefix(&mut self, prefix: &str) -> Self {
+        let mut map = Map::new();
+        for (k, v) in self {
+            if let Some(stripped) = k.strip_prefix(prefix) {
+                map.insert(stripped.to_owned(), v.take());
+            }
+        }
+        map
+    }
+
+    fn merge_to_with<T, S, K>(
+        &self,
+        dst: &mut T,
+        skip: &S,
+        key_match: KeyMatch,
+    ) -> serde_json::Result<()>
+    where
+        T: Serialize + DeserializeOwned,
+        S: ?Sized + Contains<K, str>,
+        K: Hash + Ord + Eq + Borrow<str>,
+    {
+        let mut value = serde_json::to_value(&dst)?;
+        if let Some(map) = value.as_object_mut() {
+            for (k, v) in map {
+                if !skip.contains_ref(k.as_str()) {
+                    if let Some(o) = key_match.find(self, k) {
+                        *v = o.clone();
+                    }
+                }
+            }
+        }
+        T::deserialize_in_place(value, dst)?;
+        Ok(())
+    }
+
+    fn rename_keys_with<F: FnMut(&str) -> String>(&mut self, mut f: F) {
+        case::rename_map_keys(self, &mut f);
+    }
+
+    fn project<I>(&self, fields: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let patterns = project::Patterns::new(fields);
+        project::project_map(self, &[&patterns])
+    }
+
+    fn redact<I>(&mut self, patterns: I)
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let patterns = project::Patterns::new(patterns);
+        project::redact_map(self, &[&patterns]);
+    }
+
+    fn deep_update_with(&mut self, source: Json, allow_null: bool) {
+        let mut jsn = Json::Object(std::mem::take(self));
+        jsn.deep_update_with(source, allow_null);
+        if let Json::Object(map) = jsn {
+            *self = map;
+        }
+    }
+
+    #[inline]
+    fn get_path(&self, path: &JsonPath) -> Option<&Json> {
+        self.get_member(path)
+    }
+
+    fn get_path_mut(&mut self, path: &JsonPath) -> Option<&mut Json> {
+        let (first, rest) = path.segments().split_first()?;
+        rest.iter()
+            .try_fold(self.get_mut(&first.to_key())?, |v, seg| seg.get_mut(v))
+    }
+
+    fn apply_merge_patch(&mut self, patch: Json) {
+        if patch.is_object() {
+            let mut jsn = Json::Object(std::mem::take(self));
+            patch::apply_merge_patch(&mut jsn, patch);
+            if let Json::Object(map) = jsn {
+                *self = map;
+            }
+        }
+    }
+
+    fn apply_json_patch(&mut self, patch: &Json) -> Result<(), JsonPatchError> {
+        let mut jsn = Json::Object(self.clone());
+        patch::apply_json_patch(&mut jsn, patch)?;
+        match jsn {
+            Json::Object(map) => {
+                *self = map;
+                Ok(())
+            }
+            _ => Err(JsonPatchError {
+                index: patch.as_array().map_or(0, |x| x.len().saturating_sub(1)),
+                path: String::new(),
+                kind: JsonPatchErrorKind::InvalidOperation,
+            }),
+        }
+    }
+
+    fn diff_to_json_patch(&self, target: &Self) -> Json {
+        let source = Json::Object(self.clone());
+        patch::diff_to_json_patch(&source, &Json::Object(target.clone()))
+    }
+
+    fn to_canonical_vec(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        let _ = canonical::write_map(&mut buf, self);
+        buf
+    }
+
+    fn canonical_hash(&self) -> u64 {
+        let mut hasher = canonical::Fnv1aWriter::default();
+        let _ = canonical::write_map(&mut hasher, self);
+        hasher.0
+    }
+}
+
+// ////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_get_or() {
+        let jsn = json!({"name": "Tom", "value": 100, "weight": 50.});
+
+        assert_eq!(jsn.get_or("name", "John"), "Tom");
+        // type dese not match
+        assert_eq!(jsn.get_or("name", 1), 1);
+        // index dese not match
+        assert_eq!(jsn.get_or("Name", "Json"), "Json");
+
+        assert_eq!(jsn.get_or("value", 1), 100);
+        // type dese not match
+        assert_eq!(jsn.get_or("value", "1"), "1");
+
+        assert_eq!(jsn.get_or("weight", 1.), 50.);
+        // type dese not match
+        assert_eq!(jsn.get_or("weight", "1"), "1");
+
+        let jsn = jsn.as_object().unwrap();
+
+        assert_eq!(jsn.get_or("name", "John"), "Tom");
+        // type dese not match
+        assert_eq!(jsn.get_or("name", 1), 1);
+        // index dese not match
+        assert_eq!(jsn.get_or("Name", "Json"), "Json");
+
+        assert_eq!(jsn.get_or("value", 1), 100);
+        assert_eq!(jsn.get_or_else("value", || 1), 100);
+        // type dese not match
+        assert_eq!(jsn.get_or("value", "1"), "1");
+
+        assert_eq!(jsn.get_or("weight", 1.), 50.);
+        // type dese not match
+        assert_ne!(jsn.get_or("weight", 1), 50);
+        // type dese not match
+        assert_eq!(jsn.get_or("weight", "1"), "1");
+        assert_eq!(jsn.get_or_else("weight", || "1"), "1");
+
+        let jsn = json!([1, 2., "3"]);
+        let jsn = jsn.as_array().unwrap();
+        assert_eq!(jsn.get_or(0, 2), 1);
+        // type dese not match
+        assert_eq!(jsn.get_or(1, 3), 3);
+        assert_eq!(jsn.get_or(1, 2.), 2.);
+        assert_eq!(jsn.get_or(3, "3"), "3");
+    }
+
+    #[test]
+    fn test_json_insert() {
+        let mut jsn = json!({});
+
+        jsn.insert_s("name", "tom");
+        jsn.insert_s("age", 16);
+
+        assert_eq!(jsn.get_or("name", ""), "tom");
+        assert_eq!(jsn.get_or("age", 16), 16);
+    }
+
+    #[test]
+    fn test_json_merge() {
+        let jsn1 = json!({"1": 11, "2": 22, "3": 33});
+        let mut jsn2 = json!({"1": 1, "2": 2});
+        assert!(jsn1.merge_to(&mut jsn2, &["2"]).is_ok());
+
+        assert_eq!(jsn2.get_or("1", 0i32), 11);
+        assert_eq!(jsn2.get_or("2", 0i32), 2);
+        assert_eq!(jsn2.get_or("3", 0i32), 0);
+    }
+}
@@ -0,0 +1,447 @@
+//! RFC 7386 JSON Merge Patch and RFC 6902 JSON Patch.
+
+use super::{JsonPath, JsonPathSegment};
+use ::serde::{Deserialize, Serialize};
+use ::serde_json::{json, Number, Value as Json};
+use ::std::fmt;
+
+/// An operation of RFC 6902 JSON Patch, the paths are JSON Pointers.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum JsonPatchOp {
+    Add { path: String, value: Json },
+    Remove { path: String },
+    Replace { path: String, value: Json },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    Test { path: String, value: Json },
+}
+
+/// Kind of a [`JsonPatchError`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JsonPatchErrorKind {
+    /// The patch is not an array of valid operations.
+    InvalidOperation,
+    /// The path is not a valid JSON Pointer.
+    InvalidPointer,
+    /// The path or its parent does not exist.
+    NotFound,
+    /// The value is moved into one of its children.
+    MoveIntoChild,
+    /// The value of a `test` operation does not match.
+    TestFailed,
+}
+
+/// Error of applying a JSON Patch, the document is left unchanged.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JsonPatchError {
+    /// The index of the failed operation.
+    pub index: usize,
+    /// The path of the failed operation.
+    pub path: String,
+    pub kind: JsonPatchErrorKind,
+}
+
+impl fmt::Display for JsonPatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self.kind {
+            JsonPatchErrorKind::InvalidOperation => "invalid operation",
+            JsonPatchErrorKind::InvalidPointer => "invalid pointer",
+            JsonPatchErrorKind::NotFound => "path not found",
+            JsonPatchErrorKind::MoveIntoChild => "cannot move a value into its child",
+            JsonPatchErrorKind::TestFailed => "test failed",
+        };
+        write!(
+            f,
+            "JSON patch operation {} at {:?}: {}",
+            self.index, self.path, reason
+        )
+    }
+}
+
+impl std::error::Error for JsonPatchError {}
+
+impl JsonPatchOp {
+    /// Get the target path.
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Add { path, .. }
+            | Self::Remove { path }
+            | Self::Replace { path, .. }
+            | Self::Move { path, .. }
+            | Self::Copy { path, .. }
+            | Self::Test { path, .. } => path,
+        }
+    }
+
+    /// Apply the operation to a document, which may be partially modified on error.
+    fn apply(&self, doc: &mut Json) -> Result<(), JsonPatchErrorKind> {
+        let parse =
+            |s: &str| JsonPath::from_pointer(s).map_err(|_| JsonPatchErrorKind::InvalidPointer);
+        match self {
+            Self::Add { path, value } => add(doc, &parse(path)?, value.clone()),
+            Self::Remove { path } => remove(doc, &parse(path)?).map(|_| ()),
+            Self::Replace { path, value } => {
+                let target = parse(path)?
+                    .get_mut(doc)
+                    .ok_or(JsonPatchErrorKind::NotFound)?;
+                *target = value.clone();
+                Ok(())
+            }
+            Self::Move { from, path } => {
+                let (from, path) = (parse(from)?, parse(path)?);
+                if from == path {
+                    return from
+                        .get(doc)
+                        .map(|_| ())
+                        .ok_or(JsonPatchErrorKind::NotFound);
+                }
+                if path.starts_with(&from) {
+                    return Err(JsonPatchErrorKind::MoveIntoChild);
+                }
+                let value = remove(doc, &from)?;
+                add(doc, &path, value)
+            }
+            Self::Copy { from, path } => {
+                let value = parse(from)?
+                    .get(doc)
+                    .ok_or(JsonPatchErrorKind::NotFound)?
+                    .clone();
+                add(doc, &parse(path)?, value)
+            }
+            Self::Test { path, value } => match parse(path)?.get(doc) {
+                Some(v) if json_eq(v, value) => Ok(()),
+                Some(_) => Err(JsonPatchErrorKind::TestFailed),
+                None => Err(JsonPatchErrorKind::NotFound),
+            },
+        }
+    }
+}
+
+fn add(doc: &mut Json, path: &JsonPath, value: Json) -> Result<(), JsonPatchErrorKind> {
+    let Some((parent, last)) = path.split_last() else {
+        *doc = value;
+        return Ok(());
+    };
+    match parent.get_mut(doc) {
+        Some(Json::Object(map)) => {
+            map.insert(last.to_key(), value);
+            Ok(())
+        }
+        Some(Json::Array(array)) => {
+            if *last == JsonPathSegment::Key("-".to_owned()) {
+                array.push(value);
+                return Ok(());
+            }
+            match last.to_index() {
+                Some(i) if i <= array.len() => {
+                    array.insert(i, value);
+                    Ok(())
+                }
+                _ => Err(JsonPatchErrorKind::NotFound),
+            }
+        }
+        _ => Err(JsonPatchErrorKind::NotFound),
+    }
+}
+
+fn remove(doc: &mut Json, path: &JsonPath) -> Result<Json, JsonPatchErrorKind> {
+    let (parent, last) = path.split_last().ok_or(JsonPatchErrorKind::NotFound)?;
+    match parent.get_mut(doc) {
+        Some(Json::Object(map)) => map.remove(&last.to_key()),
+        Some(Json::Array(array)) => match last.to_index() {
+            Some(i) if i < array.len() => Some(array.remove(i)),
+            _ => None,
+        },
+        _ => None,
+    }
+    .ok_or(JsonPatchErrorKind::NotFound)
+}
+
+/// Compare JSON values, numbers are compared by their values, e.g. `1` equals `1.0`.
+pub(super) fn json_eq(a: &Json, b: &Json) -> bool {
+    match (a, b) {
+        (Json::Number(x), Json::Number(y)) => number_eq(x, y),
+        (Json::Array(x), Json::Array(y)) => {
+            x.len() == y.len() && x.iter().zip(y).all(|(a, b)| json_eq(a, b))
+        }
+        (Json::Object(x), Json::Object(y)) => {
+            x.len() == y.len()
+                && x.iter()
+                    .all(|(k, a)| y.get(k).is_some_and(|b| json_eq(a, b)))
+        }
+        _ => a == b,
+    }
+}
+
+/// Compare numbers by their values, integers are compared exactly instead of as doubles,
+/// so that distinct 64-bit IDs are never equal.
+fn number_eq(x: &Number, y: &Number) -> bool {
+    fn int(n: &Number) -> Option<i128> {
+        n.as_i64()
+            .map(i128::from)
+            .or_else(|| n.as_u64().map(i128::from))
+    }
+    // An integral double equals an integer only if it has exactly the same value.
+    fn float_int_eq(v: f64, n: i128) -> bool {
+        v.fract() == 0.0 && v.abs() < 2f64.powi(127) && v as i128 == n
+    }
+
+    match (x.is_f64(), y.is_f64()) {
+        (false, false) => int(x) == int(y),
+        (true, true) => x.as_f64() == y.as_f64(),
+        (true, false) => matches!((x.as_f64(), int(y)), (Some(v), Some(n)) if float_int_eq(v, n)),
+        (false, true) => matches!((y.as_f64(), int(x)), (Some(v), Some(n)) if float_int_eq(v, n)),
+    }
+}
+
+/// Apply an RFC 7386 JSON Merge Patch.
+pub(super) fn apply_merge_patch(doc: &mut Json, patch: Json) {
+    let Json::Object(patch) = patch else {
+        *doc = patch;
+        return;
+    };
+    if !doc.is_object() {
+        *doc = Json::Object(Default::default());
+    }
+    let Json::Object(map) = doc else {
+        unreachable!()
+    };
+    for (k, v) in patch {
+        if v.is_null() {
+            map.remove(&k);
+        } else {
+            apply_merge_patch(map.entry(k).or_insert(Json::Null), v);
+        }
+    }
+}
+
+/// Apply an RFC 6902 JSON Patch atomically.
+pub(super) fn apply_json_patch(doc: &mut Json, patch: &Json) -> Result<(), JsonPatchError> {
+    let Some(ops) = patch.as_array() else {
+        return Err(JsonPatchError {
+            index: 0,
+            path: String::new(),
+            kind: JsonPatchErrorKind::InvalidOperation,
+        });
+    };
+    let mut result = doc.clone();
+    for (index, op) in ops.iter().enumerate() {
+        let error = |kind| JsonPatchError {
+            index,
+            path: op["path"].as_str().unwrap_or_default().to_owned(),
+            kind,
+        };
+        let op = JsonPatchOp::deserialize(op)
+            .map_err(|_| error(JsonPatchErrorKind::InvalidOperation))?;
+        op.apply(&mut result).map_err(error)?;
+    }
+    *doc = result;
+    Ok(())
+}
+
+/// Generate a JSON Patch which transforms `source` into `target`.
+pub(super) fn diff_to_json_patch(source: &Json, target: &Json) -> Json {
+    let mut ops = Vec::new();
+    diff(source, target, &mut JsonPath::new(), &mut ops);
+    Json::Array(ops)
+}
+
+fn diff(source: &Json, target: &Json, path: &mut JsonPath, ops: &mut Vec<Json>) {
+    match (source, target) {
+        (Json::Object(a), Json::Object(b)) => {
+            for (k, v) in a {
+                path.push_key(k.as_str());
+                match b.get(k) {
+                    Some(x) => diff(v, x, path, ops),
+                    None => ops.push(json!({"op": "remove", "path": path.to_pointer()})),
+                }
+                path.pop();
+            }
+            for (k, v) in b {
+                if !a.contains_key(k) {
+                    path.push_key(k.as_str());
+                    ops.push(json!({"op": "add", "path": path.to_pointer(), "value": v}));
+                    path.pop();
+                }
+            }
+        }
+        (Json::Array(a), Json::Array(b)) => {
+            for (i, (x, y)) in a.iter().zip(b).enumerate() {
+                path.push_index(i);
+                diff(x, y, path, ops);
+                path.pop();
+            }
+            // Remove from the end so that the indices stay valid.
+            for i in (b.len()..a.len()).rev() {
+                path.push_index(i);
+                ops.push(json!({"op": "remove", "path": path.to_pointer()}));
+                path.pop();
+            }
+            for (i, v) in b.iter().enumerate().skip(a.len()) {
+                path.push_index(i);
+                ops.push(json!({"op": "add", "path": path.to_pointer(), "value": v}));
+                path.pop();
+            }
+        }
+        _ if source != target => {
+            ops.push(json!({"op": "replace", "path": path.to_pointer(), "value": target}));
+        }
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_merge_patch() {
+        // Examples from RFC 7386
+        let cases = [
+            (json!({"a": "b"}), json!({"a": "c"}), json!({"a": "c"})),
+            (
+                json!({"a": "b"}),
+                json!({"b": "c"}),
+                json!({"a": "b", "b": "c"}),
+            ),
+            (json!({"a": "b"}), json!({"a": null}), json!({})),
+            (
+                json!({"a": [{"b": "c"}]}),
+                json!({"a": [1]}),
+                json!({"a": [1]}),
+            ),
+            (json!(["a", "b"]), json!(["c", "d"]), json!(["c", "d"])),
+            (json!({"a": "foo"}), json!("bar"), json!("bar")),
+            (
+                json!({"e": null}),
+                json!({"a": 1}),
+                json!({"e": null, "a": 1}),
+            ),
+            (
+                json!([1, 2]),
+                json!({"a": "b", "c": null}),
+                json!({"a": "b"}),
+            ),
+            (
+                json!({}),
+                json!({"a": {"bb": {"ccc": null}}}),
+                json!({"a": {"bb": {}}}),
+            ),
+        ];
+        for (mut doc, patch, expected) in cases {
+            doc.apply_merge_patch(patch);
+            assert_eq!(doc, expected);
+        }
+    }
+
+    #[test]
+    fn test_json_eq() {
+        use super::json_eq;
+
+        assert!(json_eq(&json!(1), &json!(1.0)));
+        assert!(json_eq(&json!(-1), &json!(-1.0)));
+        assert!(json_eq(&json!([1, {"a": 2.0}]), &json!([1.0, {"a": 2}])));
+        assert!(!json_eq(&json!(1), &json!(1.5)));
+        // Integers above 2^53 are compared exactly, not as rounded doubles.
+        let (a, b) = (json!(9007199254740993_u64), json!(9007199254740992_u64));
+        assert!(!json_eq(&a, &b));
+        assert!(!json_eq(&a, &json!(9007199254740992.0)));
+        assert!(json_eq(&b, &json!(9007199254740992.0)));
+        assert!(!json_eq(&json!(u64::MAX), &json!(-1)));
+        assert!(json_eq(&json!(u64::MAX), &json!(u64::MAX)));
+
+        let mut doc = json!({"id": 9007199254740993_u64});
+        let patch = json!([{"op": "test", "path": "/id", "value": 9007199254740992_u64}]);
+        assert_eq!(
+            doc.apply_json_patch(&patch).unwrap_err().kind,
+            JsonPatchErrorKind::TestFailed
+        );
+        let schema = JsonSchema::integer().enum_values([json!(9007199254740992_u64)]);
+        assert!(!schema.is_valid(&json!(9007199254740993_u64)));
+    }
+
+    #[test]
+    fn test_json_patch() {
+        let mut doc = json!({"a": {"b": [1, 2]}, "c": "x", "d~/": 0});
+        let patch = json!([
+            {"op": "test", "path": "/a/b/0", "value": 1.0},
+            {"op": "add", "path": "/a/b/1", "value": 9},
+            {"op": "add", "path": "/a/b/-", "value": 3},
+            {"op": "remove", "path": "/c"},
+            {"op": "replace", "path": "/d~0~1", "value": 1},
+            {"op": "copy", "from": "/a/b", "path": "/e"},
+            {"op": "move", "from": "/a/b/0", "path": "/f"},
+        ]);
+        doc.apply_json_patch(&patch).unwrap();
+        assert_eq!(
+            doc,
+            json!({"a": {"b": [9, 2, 3]}, "d~/": 1, "e": [1, 9, 2, 3], "f": 1})
+        );
+
+        let failures = [
+            (
+                json!({"op": "test", "path": "/f", "value": 2}),
+                JsonPatchErrorKind::TestFailed,
+            ),
+            (
+                json!({"op": "remove", "path": "/x"}),
+                JsonPatchErrorKind::NotFound,
+            ),
+            (
+                json!({"op": "add", "path": "/a/b/4", "value": 0}),
+                JsonPatchErrorKind::NotFound,
+            ),
+            (
+                json!({"op": "add", "path": "a", "value": 0}),
+                JsonPatchErrorKind::InvalidPointer,
+            ),
+            (
+                json!({"op": "move", "from": "/a", "path": "/a/x"}),
+                JsonPatchErrorKind::MoveIntoChild,
+            ),
+            (
+                json!({"op": "nop", "path": "/a"}),
+                JsonPatchErrorKind::InvalidOperation,
+            ),
+        ];
+        for (op, kind) in failures {
+            let before = doc.clone();
+            let patch = json!([{"op": "remove", "path": "/d~0~1"}, op]);
+            let err = doc.apply_json_patch(&patch).unwrap_err();
+            assert_eq!((err.index, err.kind), (1, kind));
+            assert_eq!(doc, before);
+        }
+
+        let mut map = doc.as_object().unwrap().clone();
+        map.apply_json_patch(&json!([{"op": "remove", "path": "/e"}]))
+            .unwrap();
+        assert!(!map.contains_key("e"));
+        assert!(map
+            .apply_json_patch(&json!([{"op": "replace", "path": "", "value": 1}]))
+            .is_err());
+    }
+
+    #[test]
+    fn test_json_diff() {
+        let a = json!({"a": {"b": [1, 2, 3], "c": "x"}, "d": 1, "e/f": true});
+        let b = json!({"a": {"b": [1, 5], "c": "x", "g": null}, "d": [1], "h": 2});
+        let patch = a.diff_to_json_patch(&b);
+        assert_eq!(
+            patch,
+            json!([
+                {"op": "replace", "path": "/a/b/1", "value": 5},
+                {"op": "remove", "path": "/a/b/2"},
+                {"op": "add", "path": "/a/g", "value": null},
+                {"op": "replace", "path": "/d", "value": [1]},
+                {"op": "remove", "path": "/e~1f"},
+                {"op": "add", "path": "/h", "value": 2},
+            ])
+        );
+        let mut c = a.clone();
+        c.apply_json_patch(&patch).unwrap();
+        assert_eq!(c, b);
+        assert_eq!(b.diff_to_json_patch(&b), json!([]));
+    }
+}
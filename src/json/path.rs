@@ -0,0 +1,430 @@
+//! Paths into nested JSON values, in the syntax of RFC 6901 JSON Pointer (`/a/b/3/c`)
+//! or a dotted path with brackets (`a.b[3].c`).
+
+use super::JsonIndexed;
+use ::serde_json::{map::Map, Value as Json};
+use ::std::{fmt, str::FromStr};
+
+/// A segment of a [`JsonPath`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum JsonPathSegment {
+    /// A key of an object, or an index of an array if it's a decimal number.
+    Key(String),
+    /// An index of an array, or a key of an object in decimal.
+    Index(usize),
+}
+
+impl JsonPathSegment {
+    /// Get the child of an object or an array.
+    pub fn get<'a>(&self, jsn: &'a Json) -> Option<&'a Json> {
+        match (self, jsn) {
+            (Self::Key(k), Json::Object(map)) => map.get(k),
+            (Self::Key(k), Json::Array(array)) => array.get(Self::parse_index(k)?),
+            (Self::Index(i), Json::Object(map)) => map.get(&i.to_string()),
+            (Self::Index(i), Json::Array(array)) => array.get(*i),
+            _ => None,
+        }
+    }
+
+    /// Get the mutable child of an object or an array.
+    pub fn get_mut<'a>(&self, jsn: &'a mut Json) -> Option<&'a mut Json> {
+        match (self, jsn) {
+            (Self::Key(k), Json::Object(map)) => map.get_mut(k),
+            (Self::Key(k), Json::Array(array)) => array.get_mut(Self::parse_index(k)?),
+            (Self::Index(i), Json::Object(map)) => map.get_mut(&i.to_string()),
+            (Self::Index(i), Json::Array(array)) => array.get_mut(*i),
+            _ => None,
+        }
+    }
+
+    /// Get the key of an object.
+    pub fn to_key(&self) -> String {
+        match self {
+            Self::Key(k) => k.clone(),
+            Self::Index(i) => i.to_string(),
+        }
+    }
+
+    /// Get the index of an array, the decimal number must not have leading zeros.
+    pub fn to_index(&self) -> Option<usize> {
+        match self {
+            Self::Key(k) => Self::parse_index(k),
+            Self::Index(i) => Some(*i),
+        }
+    }
+
+    fn parse_index(s: &str) -> Option<usize> {
+        match s.as_bytes() {
+            [b'0'] => Some(0),
+            [b'1'..=b'9', rest @ ..] if rest.iter().all(u8::is_ascii_digit) => s.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Error of parsing a [`JsonPath`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JsonPathError {
+    /// The path string.
+    pub path: String,
+    /// The byte position where the error occurred.
+    pub position: usize,
+}
+
+impl fmt::Display for JsonPathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid JSON path {:?} at {}", self.path, self.position)
+    }
+}
+
+impl std::error::Error for JsonPathError {}
+
+/// A parsed path into a nested JSON value, which can be cached and reused.
+///
+/// # Examples
+///
+/// ```
+/// use serde_json::json;
+/// use xelf::json::*;
+///
+/// let jsn = json!({"a": {"b": [{"c": 1}, {"c": 2}]}});
+/// let path = JsonPath::parse("a.b[1].c").unwrap();
+/// assert_eq!(jsn.get_or(&path, 0), 2);
+/// assert_eq!(path.to_pointer(), "/a/b/1/c");
+/// assert_eq!(jsn.get_or(&JsonPath::parse("/a/b/1/c").unwrap(), 0), 2);
+///
+/// let path = JsonPath::parse("a.x").unwrap();
+/// assert_eq!(jsn.get_or(&path, 3), 3);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct JsonPath {
+    segments: Vec<JsonPathSegment>,
+}
+
+impl JsonPath {
+    /// Create an empty path which refers to the root value.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse a JSON Pointer if it starts with `/`, or a dotted path otherwise.
+    ///
+    /// A dotted path may start with `$`, and a key with special characters can be quoted,
+    /// e.g. `$.a["b.c"][0]`.
+    pub fn parse(s: &str) -> Result<Self, JsonPathError> {
+        if s.is_empty() || s.starts_with('/') {
+            Self::from_pointer(s)
+        } else {
+            Self::from_dotted(s)
+        }
+    }
+
+    /// Parse an RFC 6901 JSON Pointer, e.g. `/a/b~1c/0`.
+    pub fn from_pointer(s: &str) -> Result<Self, JsonPathError> {
+        let error = |position| JsonPathError {
+            path: s.to_owned(),
+            position,
+        };
+        if s.is_empty() {
+            return Ok(Self::new());
+        }
+        let Some(rest) = s.strip_prefix('/') else {
+            return Err(error(0));
+        };
+        let mut segments = Vec::new();
+        let mut pos = 1;
+        for token in rest.split('/') {
+            let mut key = String::with_capacity(token.len());
+            let mut chars = token.char_indices();
+            while let Some((i, c)) = chars.next() {
+                match c {
+                    '~' => match chars.next() {
+                        Some((_, '0')) => key.push('~'),
+                        Some((_, '1')) => key.push('/'),
+                        _ => return Err(error(pos + i)),
+                    },
+                    c => key.push(c),
+                }
+            }
+            segments.push(JsonPathSegment::Key(key));
+            pos += token.len() + 1;
+        }
+        Ok(Self { segments })
+    }
+
+    fn from_dotted(s: &str) -> Result<Self, JsonPathError> {
+        let error = |position| JsonPathError {
+            path: s.to_owned(),
+            position,
+        };
+        let bytes = s.as_bytes();
+        let mut segments = Vec::new();
+        // The root marker, e.g. `$.a` or `$[0]`.
+        let mut pos = match bytes {
+            [b'$'] | [b'$', b'.' | b'[', ..] => 1,
+            _ => 0,
+        };
+        let is_key_end = |c: u8| matches!(c, b'.' | b'[' | b']');
+        while pos < bytes.len() {
+            match bytes[pos] {
+                b'[' => {
+                    pos += 1;
+                    match bytes.get(pos) {
+                        Some(&quote @ (b'"' | b'\'')) => {
+                            let mut key = String::new();
+                            let mut chars = s[pos + 1..].char_indices();
+                            let end = loop {
+                                match chars.next() {
+                                    Some((i, c)) if c as u32 == quote as u32 => break pos + 1 + i,
+                                    Some((i, '\\')) => match chars.next() {
+                                        Some((_, c)) => key.push(c),
+                                        None => return Err(error(pos + 1 + i)),
+                                    },
+                                    Some((_, c)) => key.push(c),
+                                    None => return Err(error(s.len())),
+                                }
+                            };
+                            if bytes.get(end + 1) != Some(&b']') {
+                                return Err(error(end + 1));
+                            }
+                            segments.push(JsonPathSegment::Key(key));
+                            pos = end + 2;
+                        }
+                        _ => {
+                            let end = s[pos..].find(']').ok_or_else(|| error(s.len()))? + pos;
+                            let index = s[pos..end].trim().parse().map_err(|_| error(pos))?;
+                            segments.push(JsonPathSegment::Index(index));
+                            pos = end + 1;
+                        }
+                    }
+                }
+                b'.' if pos + 1 < bytes.len() && !is_key_end(bytes[pos + 1]) => {
+                    let end = s[pos + 1..]
+                        .find(['.', '[', ']'])
+                        .map_or(s.len(), |x| x + pos + 1);
+                    segments.push(JsonPathSegment::Key(s[pos + 1..end].to_owned()));
+                    pos = end;
+                }
+                b'.' => return Err(error(pos)),
+                _ if pos == 0 => {
+                    let end = s.find(['.', '[', ']']).unwrap_or(s.len());
+                    segments.push(JsonPathSegment::Key(s[pos..end].to_owned()));
+                    pos = end;
+                }
+                _ => return Err(error(pos)),
+            }
+        }
+        Ok(Self { segments })
+    }
+
+    /// Get the segments.
+    #[inline]
+    pub fn segments(&self) -> &[JsonPathSegment] {
+        &self.segments
+    }
+
+    /// Check if the path refers to the root value.
+    #[inline]
+    pub fn is_root(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Append a key of an object.
+    pub fn push_key<K: Into<String>>(&mut self, key: K) -> &mut Self {
+        self.segments.push(JsonPathSegment::Key(key.into()));
+        self
+    }
+
+    /// Append an index of an array.
+    pub fn push_index(&mut self, index: usize) -> &mut Self {
+        self.segments.push(JsonPathSegment::Index(index));
+        self
+    }
+
+    /// Remove the last segment.
+    #[inline]
+    pub fn pop(&mut self) -> Option<JsonPathSegment> {
+        self.segments.pop()
+    }
+
+    /// Split into the parent path and the last segment, `None` for the root path.
+    pub fn split_last(&self) -> Option<(JsonPath, &JsonPathSegment)> {
+        let (last, parent) = self.segments.split_last()?;
+        Some((
+            JsonPath {
+                segments: parent.to_vec(),
+            },
+            last,
+        ))
+    }
+
+    /// Check if this path is a prefix of or equal to another.
+    pub fn starts_with(&self, other: &JsonPath) -> bool {
+        other.segments.len() <= self.segments.len()
+            && other
+                .segments
+                .iter()
+                .zip(&self.segments)
+                .all(|(a, b)| a.to_key() == b.to_key())
+    }
+
+    /// Convert into an RFC 6901 JSON Pointer.
+    pub fn to_pointer(&self) -> String {
+        let mut s = String::new();
+        for seg in &self.segments {
+            s.push('/');
+            s.push_str(&seg.to_key().replace('~', "~0").replace('/', "~1"));
+        }
+        s
+    }
+
+    /// Get the value referred by the path.
+    pub fn get<'a>(&self, jsn: &'a Json) -> Option<&'a Json> {
+        self.segments.iter().try_fold(jsn, |v, seg| seg.get(v))
+    }
+
+    /// Get the mutable value referred by the path.
+    pub fn get_mut<'a>(&self, jsn: &'a mut Json) -> Option<&'a mut Json> {
+        self.segments.iter().try_fold(jsn, |v, seg| seg.get_mut(v))
+    }
+}
+
+impl FromStr for JsonPath {
+    type Err = JsonPathError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Format as a dotted path, e.g. `a["b.c"][0]`.
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, seg) in self.segments.iter().enumerate() {
+            match seg {
+                JsonPathSegment::Index(n) => write!(f, "[{}]", n)?,
+                JsonPathSegment::Key(k) if Self::is_plain_key(k, i == 0) => {
+                    if i > 0 {
+                        f.write_str(".")?;
+                    }
+                    f.write_str(k)?;
+                }
+                JsonPathSegment::Key(k) => {
+                    write!(f, "[\"{}\"]", k.replace('\\', "\\\\").replace('"', "\\\""))?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl JsonPath {
+    /// Check if a key can be written without quotes in a dotted path.
+    fn is_plain_key(k: &str, first: bool) -> bool {
+        !(k.is_empty()
+            || k.contains(['.', '[', ']', '"', '\'', '/'])
+            || (first && k.starts_with('$')))
+    }
+}
+
+impl From<Vec<JsonPathSegment>> for JsonPath {
+    #[inline]
+    fn from(segments: Vec<JsonPathSegment>) -> Self {
+        Self { segments }
+    }
+}
+
+impl JsonIndexed<&JsonPath> for Json {
+    #[inline]
+    fn get_member(&self, path: &JsonPath) -> Option<&Json> {
+        path.get(self)
+    }
+}
+
+impl JsonIndexed<&JsonPath> for Map<String, Json> {
+    fn get_member(&self, path: &JsonPath) -> Option<&Json> {
+        let (first, rest) = path.segments.split_first()?;
+        rest.iter()
+            .try_fold(self.get(&first.to_key())?, |v, seg| seg.get(v))
+    }
+}
+
+impl JsonIndexed<&JsonPath> for Vec<Json> {
+    fn get_member(&self, path: &JsonPath) -> Option<&Json> {
+        let (first, rest) = path.segments.split_first()?;
+        rest.iter()
+            .try_fold(self.get(first.to_index()?)?, |v, seg| seg.get(v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_path() {
+        let mut jsn = json!({
+            "a": {"b": [{"c": 1}, {"c": 2.5, "d": "x"}], "e.f": true, "1": "one"},
+            "m/n": {"~": 3},
+        });
+        let cases = [
+            ("a.b[1].c", "/a/b/1/c"),
+            ("$.a.b.1.d", "/a/b/1/d"),
+            ("a[\"e.f\"]", "/a/e.f"),
+            ("a['1']", "/a/1"),
+            ("a[1]", "/a/1"),
+            ("[\"m/n\"][\"~\"]", "/m~1n/~0"),
+        ];
+        for (dotted, pointer) in cases {
+            let a = JsonPath::parse(dotted).unwrap();
+            let b = JsonPath::parse(pointer).unwrap();
+            assert_eq!(a.to_pointer(), pointer);
+            assert_eq!(a.get(&jsn), b.get(&jsn), "{}", dotted);
+            assert!(a.get(&jsn).is_some(), "{}", dotted);
+            assert_eq!(jsn.pointer(pointer), a.get(&jsn));
+            assert_eq!(
+                JsonPath::parse(&a.to_string()).unwrap().get(&jsn),
+                a.get(&jsn)
+            );
+        }
+
+        let path: JsonPath = "a.b[1].c".parse().unwrap();
+        assert_eq!(jsn.get_or(&path, 0.), 2.5);
+        assert_eq!(jsn.get_or(&path, "s"), "s");
+        assert_eq!(jsn.as_object().unwrap().get_or(&path, 0.), 2.5);
+        let path = JsonPath::parse("a.e\\.f").unwrap();
+        assert!(jsn.get_member(&path).is_none());
+        let path = JsonPath::parse("[0].c").unwrap();
+        assert_eq!(jsn["a"]["b"].as_array().unwrap().get_or(&path, 0), 1);
+        assert_eq!(jsn.get_or(&JsonPath::new(), "root"), "root");
+
+        let path = JsonPath::parse("a.b[0].c").unwrap();
+        *jsn.get_path_mut(&path).unwrap() = json!(10);
+        assert_eq!(jsn.get_path(&path), Some(&json!(10)));
+        assert!(jsn
+            .get_path_mut(&JsonPath::parse("a.b[2]").unwrap())
+            .is_none());
+
+        for s in [
+            "a..b", "a.", "a[", "a[x]", "a[\"b]", "a[\"b\"", "a]", "/a~2", "a[1",
+        ] {
+            assert!(JsonPath::parse(s).is_err(), "{}", s);
+        }
+        let path = JsonPath::parse("$ref[\"x\\\"y\"]").unwrap();
+        assert_eq!(path.to_pointer(), "/$ref/x\"y");
+        assert_eq!(path.to_string(), "[\"$ref\"][\"x\\\"y\"]");
+        assert_eq!(JsonPath::parse(&path.to_string()), Ok(path));
+        assert!(JsonPath::parse("$").unwrap().is_root());
+        assert_eq!(
+            JsonPath::parse("a[x]").unwrap_err(),
+            JsonPathError {
+                path: "a[x]".to_owned(),
+                position: 2
+            }
+        );
+    }
+}
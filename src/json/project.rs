@@ -0,0 +1,221 @@
+//! Field projection and redaction of JSON objects by dotted path patterns.
+//!
+//! A pattern is a dotted path like `profile.token`, where `*` matches any single key.
+//! Arrays are transparent, `items.id` applies to the `id` of all elements of `items`.
+//! `\` escapes the next character, e.g. `a\.b` is the key `a.b` and `\*` is the key `*`.
+
+use ::serde_json::{json, map::Map, Value as Json};
+use ::std::collections::HashMap;
+
+/// Patterns parsed into a trie of path segments, which is walked key by key,
+/// so that any depth is matched without enumerating wildcard combinations.
+#[derive(Debug, Default)]
+pub(super) struct Patterns {
+    keys: HashMap<String, Patterns>,
+    any: Option<Box<Patterns>>,
+    end: bool,
+}
+
+impl Patterns {
+    pub(super) fn new<I>(patterns: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut root = Self::default();
+        for pattern in patterns {
+            let mut node = &mut root;
+            for (seg, any) in parse_pattern(pattern.as_ref()) {
+                node = if any {
+                    node.any.get_or_insert_with(Default::default)
+                } else {
+                    node.keys.entry(seg).or_default()
+                };
+            }
+            node.end = true;
+        }
+        root
+    }
+
+    /// Get the nodes after a key, empty if no pattern can match below it.
+    fn step<'a>(nodes: &[&'a Patterns], key: &str) -> Vec<&'a Patterns> {
+        let mut next = Vec::new();
+        for node in nodes {
+            next.extend(node.keys.get(key));
+            next.extend(node.any.as_deref());
+        }
+        next
+    }
+}
+
+/// Split a pattern into segments and whether each one is a wildcard.
+/// `\` escapes the next character, so `a\.b` is a key with a dot and `\*` is a literal `*`.
+fn parse_pattern(pattern: &str) -> Vec<(String, bool)> {
+    let mut segs = Vec::new();
+    let (mut seg, mut escaped) = (String::new(), false);
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(c) = chars.next() {
+                    seg.push(c);
+                    escaped = true;
+                }
+            }
+            '.' => {
+                let any = seg == "*" && !escaped;
+                segs.push((std::mem::take(&mut seg), any));
+                escaped = false;
+            }
+            _ => seg.push(c),
+        }
+    }
+    let any = seg == "*" && !escaped;
+    segs.push((seg, any));
+    segs
+}
+
+pub(super) fn project_map(map: &Map<String, Json>, nodes: &[&Patterns]) -> Map<String, Json> {
+    let mut result = Map::new();
+    for (k, v) in map {
+        let next = Patterns::step(nodes, k);
+        if next.iter().any(|x| x.end) {
+            result.insert(k.clone(), v.clone());
+        } else if next.is_empty() {
+            continue;
+        } else if let Some(v) = project_value(v, &next) {
+            result.insert(k.clone(), v);
+        }
+    }
+    result
+}
+
+/// Project a nested value, `None` if nothing in it matches.
+pub(super) fn project_value(jsn: &Json, nodes: &[&Patterns]) -> Option<Json> {
+    match jsn {
+        Json::Object(map) => {
+            let map = project_map(map, nodes);
+            (!map.is_empty()).then_some(Json::Object(map))
+        }
+        Json::Array(array) => {
+            let items: Vec<Option<Json>> = array.iter().map(|x| project_value(x, nodes)).collect();
+            if items.iter().all(Option::is_none) {
+                return None;
+            }
+            // Objects without matched fields are kept empty, so that the elements are aligned.
+            let items = array
+                .iter()
+                .zip(items)
+                .filter_map(|(x, v)| v.or_else(|| x.is_object().then(|| json!({}))))
+                .collect();
+            Some(Json::Array(items))
+        }
+        _ => None,
+    }
+}
+
+pub(super) fn redact_map(map: &mut Map<String, Json>, nodes: &[&Patterns]) {
+    map.retain(|k, v| {
+        let next = Patterns::step(nodes, k);
+        let keep = !next.iter().any(|x| x.end);
+        if keep && !next.is_empty() {
+            redact_value(v, &next);
+        }
+        keep
+    });
+}
+
+pub(super) fn redact_value(jsn: &mut Json, nodes: &[&Patterns]) {
+    match jsn {
+        Json::Object(map) => redact_map(map, nodes),
+        Json::Array(array) => {
+            for v in array {
+                redact_value(v, nodes);
+            }
+        }
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json::*;
+    use serde_json::json;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_json_project_redact() {
+        let jsn = json!({
+            "id": 1,
+            "name": "tom",
+            "password_hash": "x",
+            "salt": "y",
+            "profile": {"bio": "b", "token": "t", "tags": ["a"]},
+            "devices": [{"id": 1, "token": "t1"}, {"id": 2}, {"model": "m"}, 3],
+            "empty": {},
+        });
+
+        assert_eq!(
+            jsn.project(&["id", "profile.bio", "devices.id", "empty.x"]),
+            json!({
+                "id": 1,
+                "profile": {"bio": "b"},
+                "devices": [{"id": 1}, {"id": 2}, {}],
+            })
+        );
+        assert_eq!(
+            jsn.project(&vec!["*.token", "name"]),
+            json!({
+                "name": "tom",
+                "profile": {"token": "t"},
+                "devices": [{"token": "t1"}, {}, {}],
+            })
+        );
+        assert_eq!(jsn.project(&None::<&str>), json!({}));
+        let list = json!([{"id": 1, "name": "a"}, {"id": 2}]);
+        assert_eq!(list.project(&["id"]), json!([{"id": 1}, {"id": 2}]));
+
+        let mut redacted = jsn.clone();
+        let patterns: HashSet<&str> = ["password_hash", "salt", "*.token", "profile.*.x"].into();
+        redacted.redact(&patterns);
+        assert_eq!(
+            redacted,
+            json!({
+                "id": 1,
+                "name": "tom",
+                "profile": {"bio": "b", "tags": ["a"]},
+                "devices": [{"id": 1}, {"id": 2}, {"model": "m"}, 3],
+                "empty": {},
+            })
+        );
+        let mut map = jsn.as_object().unwrap().clone();
+        map.redact(&["*"]);
+        assert!(map.is_empty());
+
+        // Wildcards match at any depth.
+        let mut deep = json!({"a": {"b": {"c": {"d": {"e": {"f": {"g": {"h": {
+            "x": {"secret": 1, "ok": 2},
+            "y": [{"secret": 3}],
+        }}}}}}}}});
+        deep.redact(&["a.b.c.d.e.f.g.h.*.secret"]);
+        assert_eq!(
+            deep["a"]["b"]["c"]["d"]["e"]["f"]["g"]["h"],
+            json!({"x": {"ok": 2}, "y": [{}]})
+        );
+        let mut deep =
+            json!({"a": {"b": {"c": {"d": {"e": {"f": {"g": {"h": {"i": {"j": 1, "k": 2}}}}}}}}}});
+        deep.redact(&["*.*.*.*.*.*.*.*.*.j"]);
+        assert_eq!(
+            deep.project(&["*.*.*.*.*.*.*.*.*.*"]),
+            json!({"a": {"b": {"c": {"d": {"e": {"f": {"g": {"h": {"i": {"k": 2}}}}}}}}}})
+        );
+
+        // Keys with `.` or a literal `*` are escaped by `\\`.
+        let mut keys = json!({"a.b": 1, "a": {"b": 2}, "*": 3, "c": 4});
+        assert_eq!(keys.project(&[r"a\.b"]), json!({"a.b": 1}));
+        assert_eq!(keys.project(&["a.b"]), json!({"a": {"b": 2}}));
+        assert_eq!(keys.project(&[r"\*"]), json!({"*": 3}));
+        keys.redact(&[r"\*", r"a\.b"]);
+        assert_eq!(keys, json!({"a": {"b": 2}, "c": 4}));
+    }
+}
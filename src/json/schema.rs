@@ -0,0 +1,758 @@
+//! A lightweight validator of JSON values, with rules defined in Rust
+//! or loaded from a subset of JSON Schema.
+
+use super::{patch::json_eq, JsonPath, JsonType};
+use ::serde_json::{json, map::Map, Value as Json};
+use ::std::fmt;
+
+/// Types of values in a [`JsonSchema`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum JsonSchemaType {
+    Null,
+    Boolean,
+    /// A number without a fractional part.
+    Integer,
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+impl JsonSchemaType {
+    /// Get the name of the type in JSON Schema.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Null => "null",
+            Self::Boolean => "boolean",
+            Self::Integer => "integer",
+            Self::Number => "number",
+            Self::String => "string",
+            Self::Array => "array",
+            Self::Object => "object",
+        }
+    }
+
+    /// Parse the name of a type in JSON Schema.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "null" => Self::Null,
+            "boolean" => Self::Boolean,
+            "integer" => Self::Integer,
+            "number" => Self::Number,
+            "string" => Self::String,
+            "array" => Self::Array,
+            "object" => Self::Object,
+            _ => return None,
+        })
+    }
+
+    /// Check if a JSON value is of this type.
+    pub fn matches(&self, jsn: &Json) -> bool {
+        match (self, jsn) {
+            (Self::Null, Json::Null)
+            | (Self::Boolean, Json::Bool(_))
+            | (Self::Number, Json::Number(_))
+            | (Self::String, Json::String(_))
+            | (Self::Array, Json::Array(_))
+            | (Self::Object, Json::Object(_)) => true,
+            (Self::Integer, Json::Number(n)) => {
+                n.is_i64() || n.is_u64() || n.as_f64().is_some_and(|x| x.fract() == 0.0)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for JsonSchemaType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Kind of a [`JsonValidationError`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum JsonValidationErrorKind {
+    /// A required field is missing.
+    Required,
+    /// The value is not of the expected types.
+    Type {
+        expected: Vec<JsonSchemaType>,
+        actual: JsonType,
+    },
+    Minimum(f64),
+    Maximum(f64),
+    ExclusiveMinimum(f64),
+    ExclusiveMaximum(f64),
+    MinLength(usize),
+    MaxLength(usize),
+    MinItems(usize),
+    MaxItems(usize),
+    /// The string does not match the pattern.
+    Pattern(String),
+    /// The value is not one of the allowed values.
+    Enum,
+    /// The field is not allowed by a schema without additional properties.
+    AdditionalProperty,
+}
+
+impl fmt::Display for JsonValidationErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Required => f.write_str("is required"),
+            Self::Type { expected, actual } => {
+                f.write_str("expected ")?;
+                for (i, t) in expected.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(" or ")?;
+                    }
+                    t.fmt(f)?;
+                }
+                write!(f, ", found {}", actual)
+            }
+            Self::Minimum(x) => write!(f, "must be >= {}", x),
+            Self::Maximum(x) => write!(f, "must be <= {}", x),
+            Self::ExclusiveMinimum(x) => write!(f, "must be > {}", x),
+            Self::ExclusiveMaximum(x) => write!(f, "must be < {}", x),
+            Self::MinLength(n) => write!(f, "must have at least {} characters", n),
+            Self::MaxLength(n) => write!(f, "must have at most {} characters", n),
+            Self::MinItems(n) => write!(f, "must have at least {} items", n),
+            Self::MaxItems(n) => write!(f, "must have at most {} items", n),
+            Self::Pattern(p) => write!(f, "must match the pattern {:?}", p),
+            Self::Enum => f.write_str("is not an allowed value"),
+            Self::AdditionalProperty => f.write_str("is not allowed"),
+        }
+    }
+}
+
+/// Error of a value which fails a rule of [`JsonSchema`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct JsonValidationError {
+    /// The path of the value, e.g. `a.b[3]`, empty for the root.
+    pub path: String,
+    pub kind: JsonValidationErrorKind,
+}
+
+impl fmt::Display for JsonValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "value {}", self.kind)
+        } else {
+            write!(f, "{}: {}", self.path, self.kind)
+        }
+    }
+}
+
+impl std::error::Error for JsonValidationError {}
+
+/// Errors of all values which fail a [`JsonSchema`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct JsonValidationErrors(pub Vec<JsonValidationError>);
+
+impl JsonValidationErrors {
+    /// Convert into an object which maps paths to the messages of their first errors.
+    pub fn to_json(&self) -> Json {
+        let mut map = Map::new();
+        for e in &self.0 {
+            map.entry(e.path.clone())
+                .or_insert_with(|| json!(e.kind.to_string()));
+        }
+        Json::Object(map)
+    }
+}
+
+impl fmt::Display for JsonValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, e) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            e.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for JsonValidationErrors {}
+
+/// Error of loading a [`JsonSchema`] from JSON.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JsonSchemaError {
+    /// The JSON Pointer of the invalid keyword in the schema.
+    pub pointer: String,
+    pub message: String,
+}
+
+impl fmt::Display for JsonSchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid JSON schema at {:?}: {}",
+            self.pointer, self.message
+        )
+    }
+}
+
+impl std::error::Error for JsonSchemaError {}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Validation rules of a JSON value.
+///
+/// # Examples
+///
+/// ```
+/// use serde_json::json;
+/// use xelf::json::*;
+///
+/// let schema = JsonSchema::object()
+///     .required_property("name", JsonSchema::string().min_length(1))
+///     .property("age", JsonSchema::integer().minimum(0.0).maximum(150.0))
+///     .property("tags", JsonSchema::array(JsonSchema::string()).max_items(2));
+///
+/// assert!(schema.is_valid(&json!({"name": "tom", "age": 16})));
+///
+/// let errors = schema
+///     .validate(&json!({"age": -1, "tags": ["a", 1]}))
+///     .unwrap_err();
+/// assert_eq!(
+///     errors.to_json(),
+///     json!({
+///         "name": "is required",
+///         "age": "must be >= 0",
+///         "tags[1]": "expected string, found number",
+///     })
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct JsonSchema {
+    types: Vec<JsonSchemaType>,
+    enum_values: Option<Vec<Json>>,
+    minimum: Option<f64>,
+    maximum: Option<f64>,
+    exclusive_minimum: Option<f64>,
+    exclusive_maximum: Option<f64>,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    #[cfg(feature = "regex")]
+    pattern: Option<regex::Regex>,
+    min_items: Option<usize>,
+    max_items: Option<usize>,
+    items: Option<Box<JsonSchema>>,
+    properties: Vec<(String, JsonSchema)>,
+    required: Vec<String>,
+    deny_additional: bool,
+}
+
+impl JsonSchema {
+    /// Create a schema which accepts any value.
+    pub fn any() -> Self {
+        Self::default()
+    }
+
+    /// Create a schema which accepts values of the types.
+    pub fn of_types<I: IntoIterator<Item = JsonSchemaType>>(types: I) -> Self {
+        Self {
+            types: types.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    #[inline]
+    pub fn boolean() -> Self {
+        Self::of_types([JsonSchemaType::Boolean])
+    }
+
+    #[inline]
+    pub fn integer() -> Self {
+        Self::of_types([JsonSchemaType::Integer])
+    }
+
+    #[inline]
+    pub fn number() -> Self {
+        Self::of_types([JsonSchemaType::Number])
+    }
+
+    #[inline]
+    pub fn string() -> Self {
+        Self::of_types([JsonSchemaType::String])
+    }
+
+    /// Create a schema of arrays whose items are validated by `items`.
+    #[inline]
+    pub fn array(items: JsonSchema) -> Self {
+        Self::of_types([JsonSchemaType::Array]).items(items)
+    }
+
+    #[inline]
+    pub fn object() -> Self {
+        Self::of_types([JsonSchemaType::Object])
+    }
+
+    /// Accept null in addition to the types.
+    pub fn nullable(mut self) -> Self {
+        if !self.types.is_empty() && !self.types.contains(&JsonSchemaType::Null) {
+            self.types.push(JsonSchemaType::Null);
+        }
+        self
+    }
+
+    /// Only accept the values, numbers are compared by their values.
+    pub fn enum_values<I: IntoIterator<Item = Json>>(mut self, values: I) -> Self {
+        self.enum_values = Some(values.into_iter().collect());
+        self
+    }
+
+    pub fn minimum(mut self, v: f64) -> Self {
+        self.minimum = Some(v);
+        self
+    }
+
+    pub fn maximum(mut self, v: f64) -> Self {
+        self.maximum = Some(v);
+        self
+    }
+
+    pub fn exclusive_minimum(mut self, v: f64) -> Self {
+        self.exclusive_minimum = Some(v);
+        self
+    }
+
+    pub fn exclusive_maximum(mut self, v: f64) -> Self {
+        self.exclusive_maximum = Some(v);
+        self
+    }
+
+    /// Set the minimum number of characters of strings.
+    pub fn min_length(mut self, n: usize) -> Self {
+        self.min_length = Some(n);
+        self
+    }
+
+    /// Set the maximum number of characters of strings.
+    pub fn max_length(mut self, n: usize) -> Self {
+        self.max_length = Some(n);
+        self
+    }
+
+    /// Set a regular expression that strings must match, it's not anchored.
+    #[cfg(feature = "regex")]
+    pub fn pattern(mut self, re: regex::Regex) -> Self {
+        self.pattern = Some(re);
+        self
+    }
+
+    pub fn min_items(mut self, n: usize) -> Self {
+        self.min_items = Some(n);
+        self
+    }
+
+    pub fn max_items(mut self, n: usize) -> Self {
+        self.max_items = Some(n);
+        self
+    }
+
+    /// Set the schema of items of arrays.
+    pub fn items(mut self, schema: JsonSchema) -> Self {
+        self.items = Some(Box::new(schema));
+        self
+    }
+
+    /// Add or replace the schema of a field of objects.
+    pub fn property<K: Into<String>>(mut self, name: K, schema: JsonSchema) -> Self {
+        let name = name.into();
+        match self.properties.iter_mut().find(|x| x.0 == name) {
+            Some(x) => x.1 = schema,
+            None => self.properties.push((name, schema)),
+        }
+        self
+    }
+
+    /// Add or replace the schema of a required field of objects.
+    pub fn required_property<K: Into<String>>(self, name: K, schema: JsonSchema) -> Self {
+        let name = name.into();
+        self.required([name.clone()]).property(name, schema)
+    }
+
+    /// Add required fields of objects.
+    pub fn required<I>(mut self, names: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        for name in names {
+            let name = name.into();
+            if !self.required.contains(&name) {
+                self.required.push(name);
+            }
+        }
+        self
+    }
+
+    /// Set whether objects can have fields not defined by [`property`](Self::property),
+    /// allowed by default.
+    pub fn additional_properties(mut self, allowed: bool) -> Self {
+        self.deny_additional = !allowed;
+        self
+    }
+
+    /// Get the schema of a field.
+    pub fn get_property(&self, name: &str) -> Option<&JsonSchema> {
+        self.properties
+            .iter()
+            .find_map(|(k, v)| (k == name).then_some(v))
+    }
+
+    /// Validate a value, collecting errors of all values.
+    pub fn validate(&self, jsn: &Json) -> Result<(), JsonValidationErrors> {
+        let mut errors = Vec::new();
+        self.validate_at(jsn, &mut JsonPath::new(), &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(JsonValidationErrors(errors))
+        }
+    }
+
+    /// Check if a value is valid.
+    #[inline]
+    pub fn is_valid(&self, jsn: &Json) -> bool {
+        self.validate(jsn).is_ok()
+    }
+
+    fn validate_at(&self, jsn: &Json, path: &mut JsonPath, errors: &mut Vec<JsonValidationError>) {
+        use JsonValidationErrorKind as K;
+        let error = |errors: &mut Vec<JsonValidationError>, path: &JsonPath, kind| {
+            errors.push(JsonValidationError {
+                path: path.to_string(),
+                kind,
+            })
+        };
+
+        if !self.types.is_empty() && !self.types.iter().any(|t| t.matches(jsn)) {
+            let actual = JsonType::of(jsn);
+            error(
+                errors,
+                path,
+                K::Type {
+                    expected: self.types.clone(),
+                    actual,
+                },
+            );
+            return;
+        }
+        if let Some(values) = &self.enum_values {
+            if !values.iter().any(|x| json_eq(x, jsn)) {
+                error(errors, path, K::Enum);
+            }
+        }
+
+        match jsn {
+            Json::Number(n) => {
+                let v = n.as_f64().unwrap_or_default();
+                if let Some(x) = self.minimum.filter(|x| v < *x) {
+                    error(errors, path, K::Minimum(x));
+                }
+                if let Some(x) = self.maximum.filter(|x| v > *x) {
+                    error(errors, path, K::Maximum(x));
+                }
+                if let Some(x) = self.exclusive_minimum.filter(|x| v <= *x) {
+                    error(errors, path, K::ExclusiveMinimum(x));
+                }
+                if let Some(x) = self.exclusive_maximum.filter(|x| v >= *x) {
+                    error(errors, path, K::ExclusiveMaximum(x));
+                }
+            }
+            Json::String(s) => {
+                let len = s.chars().count();
+                if let Some(n) = self.min_length.filter(|n| len < *n) {
+                    error(errors, path, K::MinLength(n));
+                }
+                if let Some(n) = self.max_length.filter(|n| len > *n) {
+                    error(errors, path, K::MaxLength(n));
+                }
+                #[cfg(feature = "regex")]
+                if let Some(re) = self.pattern.as_ref().filter(|re| !re.is_match(s)) {
+                    error(errors, path, K::Pattern(re.as_str().to_owned()));
+                }
+            }
+            Json::Array(array) => {
+                if let Some(n) = self.min_items.filter(|n| array.len() < *n) {
+                    error(errors, path, K::MinItems(n));
+                }
+                if let Some(n) = self.max_items.filter(|n| array.len() > *n) {
+                    error(errors, path, K::MaxItems(n));
+                }
+                if let Some(items) = &self.items {
+                    for (i, v) in array.iter().enumerate() {
+                        path.push_index(i);
+                        items.validate_at(v, path, errors);
+                        path.pop();
+                    }
+                }
+            }
+            Json::Object(map) => {
+                for name in &self.required {
+                    if !map.contains_key(name) {
+                        path.push_key(name.clone());
+                        error(errors, path, K::Required);
+                        path.pop();
+                    }
+                }
+                for (k, v) in map {
+                    path.push_key(k.clone());
+                    match self.get_property(k) {
+                        Some(schema) => schema.validate_at(v, path, errors),
+                        None if self.deny_additional => error(errors, path, K::AdditionalProperty),
+                        None => (),
+                    }
+                    path.pop();
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// Load a schema from a subset of JSON Schema, with the keywords `type`, `enum`, `const`,
+    /// `minimum`, `maximum`, `exclusiveMinimum`, `exclusiveMaximum`, `minLength`, `maxLength`,
+    /// `pattern` (with the `regex` feature), `items`, `minItems`, `maxItems`, `properties`,
+    /// `required` and boolean `additionalProperties`. Other keywords are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use xelf::json::*;
+    ///
+    /// let schema = JsonSchema::from_json(&json!({
+    ///     "type": "object",
+    ///     "properties": {
+    ///         "state": {"enum": [0, 1, 2]},
+    ///         "nickname": {"type": ["string", "null"], "maxLength": 4},
+    ///     },
+    ///     "required": ["state"],
+    ///     "additionalProperties": false,
+    /// }))
+    /// .unwrap();
+    ///
+    /// assert!(schema.is_valid(&json!({"state": 1, "nickname": null})));
+    /// let errors = schema.validate(&json!({"state": 3, "nickname": "jerry", "x": 0}));
+    /// assert_eq!(errors.unwrap_err().0.len(), 3);
+    /// ```
+    pub fn from_json(jsn: &Json) -> Result<Self, JsonSchemaError> {
+        Self::load(jsn, &mut JsonPath::new())
+    }
+
+    fn load(jsn: &Json, pointer: &mut JsonPath) -> Result<Self, JsonSchemaError> {
+        let invalid = |pointer: &JsonPath, key: &str, message: &str| JsonSchemaError {
+            pointer: format!("{}/{}", pointer.to_pointer(), key),
+            message: message.to_owned(),
+        };
+        let map = match jsn {
+            Json::Object(map) => map,
+            // `true` is a schema which accepts any value.
+            Json::Bool(true) => return Ok(Self::any()),
+            _ => {
+                return Err(JsonSchemaError {
+                    pointer: pointer.to_pointer(),
+                    message: "expected an object".to_owned(),
+                })
+            }
+        };
+
+        let mut schema = Self::any();
+        for (key, v) in map {
+            let number = || {
+                v.as_f64()
+                    .ok_or_else(|| invalid(pointer, key, "expected a number"))
+            };
+            let count = || {
+                v.as_u64()
+                    .map(|x| x as usize)
+                    .ok_or_else(|| invalid(pointer, key, "expected a non-negative integer"))
+            };
+            match key.as_str() {
+                "type" => {
+                    let names: Vec<&Json> = match v {
+                        Json::Array(x) => x.iter().collect(),
+                        _ => vec![v],
+                    };
+                    for name in names {
+                        let t = name
+                            .as_str()
+                            .and_then(JsonSchemaType::from_name)
+                            .ok_or_else(|| invalid(pointer, key, "unknown type"))?;
+                        schema.types.push(t);
+                    }
+                }
+                "enum" => {
+                    let values = v
+                        .as_array()
+                        .ok_or_else(|| invalid(pointer, key, "expected an array"))?;
+                    schema.enum_values = Some(values.clone());
+                }
+                "const" => schema.enum_values = Some(vec![v.clone()]),
+                "minimum" => schema.minimum = Some(number()?),
+                "maximum" => schema.maximum = Some(number()?),
+                "exclusiveMinimum" => schema.exclusive_minimum = Some(number()?),
+                "exclusiveMaximum" => schema.exclusive_maximum = Some(number()?),
+                "minLength" => schema.min_length = Some(count()?),
+                "maxLength" => schema.max_length = Some(count()?),
+                "minItems" => schema.min_items = Some(count()?),
+                "maxItems" => schema.max_items = Some(count()?),
+                "pattern" => {
+                    let p = v
+                        .as_str()
+                        .ok_or_else(|| invalid(pointer, key, "expected a string"))?;
+                    #[cfg(feature = "regex")]
+                    {
+                        let re = regex::Regex::new(p)
+                            .map_err(|e| invalid(pointer, key, &e.to_string()))?;
+                        schema.pattern = Some(re);
+                    }
+                    #[cfg(not(feature = "regex"))]
+                    {
+                        let _ = p;
+                        return Err(invalid(pointer, key, "requires the `regex` feature"));
+                    }
+                }
+                "items" => {
+                    pointer.push_key(key.clone());
+                    let items = Self::load(v, pointer)?;
+                    pointer.pop();
+                    schema.items = Some(Box::new(items));
+                }
+                "properties" => {
+                    let props = v
+                        .as_object()
+                        .ok_or_else(|| invalid(pointer, key, "expected an object"))?;
+                    pointer.push_key(key.clone());
+                    for (name, v) in props {
+                        pointer.push_key(name.clone());
+                        let prop = Self::load(v, pointer)?;
+                        pointer.pop();
+                        schema.properties.push((name.clone(), prop));
+                    }
+                    pointer.pop();
+                }
+                "required" => {
+                    let names = v
+                        .as_array()
+                        .and_then(|x| x.iter().map(Json::as_str).collect::<Option<Vec<_>>>())
+                        .ok_or_else(|| invalid(pointer, key, "expected an array of strings"))?;
+                    schema = schema.required(names);
+                }
+                "additionalProperties" => {
+                    let allowed = v
+                        .as_bool()
+                        .ok_or_else(|| invalid(pointer, key, "only a boolean is supported"))?;
+                    schema.deny_additional = !allowed;
+                }
+                _ => (),
+            }
+        }
+        Ok(schema)
+    }
+}
+
+impl TryFrom<&Json> for JsonSchema {
+    type Error = JsonSchemaError;
+
+    #[inline]
+    fn try_from(jsn: &Json) -> Result<Self, Self::Error> {
+        Self::from_json(jsn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_schema() {
+        let address = JsonSchema::object()
+            .required_property("city", JsonSchema::string())
+            .property("zip", JsonSchema::integer().exclusive_minimum(0.0))
+            .additional_properties(false);
+        let schema = JsonSchema::object()
+            .required(["id", "name"])
+            .property("id", JsonSchema::integer())
+            .property("name", JsonSchema::string().min_length(2).max_length(4))
+            .property("score", JsonSchema::number().nullable().maximum(1.0))
+            .property(
+                "state",
+                JsonSchema::integer().enum_values([json!(0), json!(1)]),
+            )
+            .property("addresses", JsonSchema::array(address).min_items(1));
+
+        let valid = json!({
+            "id": 1.0,
+            "name": "tom",
+            "score": null,
+            "state": 1.0,
+            "addresses": [{"city": "x", "zip": 1}],
+            "other": true,
+        });
+        assert_eq!(schema.validate(&valid), Ok(()));
+
+        let invalid = json!({
+            "id": 1.5,
+            "name": "t",
+            "score": 2,
+            "state": 3,
+            "addresses": [{"zip": 0, "x": 1}, "a"],
+        });
+        let errors = schema.validate(&invalid).unwrap_err();
+        assert_eq!(
+            errors.to_json(),
+            json!({
+                "id": "expected integer, found number",
+                "name": "must have at least 2 characters",
+                "score": "must be <= 1",
+                "state": "is not an allowed value",
+                "addresses[0].city": "is required",
+                "addresses[0].zip": "must be > 0",
+                "addresses[0].x": "is not allowed",
+                "addresses[1]": "expected object, found string",
+            })
+        );
+        assert_eq!(
+            JsonSchema::string()
+                .validate(&json!(1))
+                .unwrap_err()
+                .to_string(),
+            "value expected string, found number"
+        );
+
+        let err =
+            JsonSchema::from_json(&json!({"properties": {"a": {"type": "int"}}})).unwrap_err();
+        assert_eq!(err.pointer, "/properties/a/type");
+        assert!(JsonSchema::from_json(&json!({"minLength": -1})).is_err());
+        assert!(JsonSchema::from_json(&json!({"items": true})).is_ok());
+
+        let schema = JsonSchema::from_json(&json!({
+            "type": "array",
+            "items": {"type": "string", "const": "a"},
+            "maxItems": 1,
+        }))
+        .unwrap();
+        assert!(schema.is_valid(&json!(["a"])));
+        assert_eq!(schema.validate(&json!(["b", "a"])).unwrap_err().0.len(), 2);
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_json_schema_pattern() {
+        let schema = JsonSchema::from_json(&json!({"pattern": "^[a-z]+$"})).unwrap();
+        assert!(schema.is_valid(&json!("abc")));
+        assert!(schema.is_valid(&json!(1)));
+        let errors = schema.validate(&json!("aBc")).unwrap_err();
+        assert_eq!(
+            errors.0[0].kind,
+            JsonValidationErrorKind::Pattern("^[a-z]+$".to_owned())
+        );
+        assert!(JsonSchema::from_json(&json!({"pattern": "("})).is_err());
+    }
+}
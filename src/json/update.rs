@@ -0,0 +1,130 @@
+//! Deep updates of JSON values with type coercion, shared by `deep_update_with`
+//! and the overlays of `ConfigLoader`.
+
+use super::{JsonPath, KeyMatch};
+use ::serde_json::{map::Map, Number, Value as Json};
+use ::std::str::FromStr;
+
+/// Update a value like `deep_update_with`, matching the keys of objects by `key_match`.
+///
+/// `f` is called with the path of each updated value, which is not an object,
+/// or an object updated by a non-object source, and whether the source is accepted,
+/// i.e. it's coerced into the type of the value or it's an ignored null.
+pub(super) fn deep_update_value<F>(
+    target: &mut Json,
+    source: Json,
+    allow_null: bool,
+    key_match: KeyMatch,
+    path: &mut JsonPath,
+    f: &mut F,
+) where
+    F: FnMut(&JsonPath, bool),
+{
+    let accepted = match target {
+        Json::Null => {
+            *target = source;
+            true
+        }
+        Json::Bool(_) => match source {
+            Json::Bool(x) => {
+                *target = Json::Bool(x);
+                true
+            }
+            Json::Number(x) => x.as_i64().map(|n| *target = Json::Bool(n != 0)).is_some(),
+            Json::String(x) => x.parse::<bool>().map(|x| *target = Json::Bool(x)).is_ok(),
+            Json::Null => update_null(target, allow_null),
+            _ => false,
+        },
+        Json::Number(_) => match source {
+            Json::Bool(x) => {
+                *target = Json::Number((x as i64).into());
+                true
+            }
+            Json::Number(x) => {
+                *target = Json::Number(x);
+                true
+            }
+            Json::String(x) => Number::from_str(&x)
+                .map(|x| *target = Json::Number(x))
+                .is_ok(),
+            Json::Null => update_null(target, allow_null),
+            _ => false,
+        },
+        Json::String(_) => match source {
+            Json::Bool(x) => {
+                *target = Json::String(x.to_string());
+                true
+            }
+            Json::Number(x) => {
+                *target = Json::String(x.to_string());
+                true
+            }
+            Json::String(x) => {
+                *target = Json::String(x);
+                true
+            }
+            Json::Null => update_null(target, allow_null),
+            _ => false,
+        },
+        Json::Array(array) => match source {
+            Json::Array(x) => {
+                *array = x;
+                true
+            }
+            Json::String(x) => serde_json::from_str(&x).map(|x| *array = x).is_ok(),
+            // An array is never nulled.
+            Json::Null => true,
+            _ => false,
+        },
+        Json::Object(map) => {
+            match source {
+                Json::Object(x) => deep_update_map(map, x, allow_null, key_match, path, f),
+                // An object is updated by a JSON string of an object.
+                Json::String(x) => match serde_json::from_str(&x) {
+                    Ok(x) => deep_update_map(map, x, allow_null, key_match, path, f),
+                    _ => f(path, false),
+                },
+                _ => f(path, false),
+            }
+            return;
+        }
+    };
+    f(path, accepted);
+}
+
+/// Update the fields of an object, see [`deep_update_value`].
+pub(super) fn deep_update_map<F>(
+    target: &mut Map<String, Json>,
+    source: Map<String, Json>,
+    allow_null: bool,
+    key_match: KeyMatch,
+    path: &mut JsonPath,
+    f: &mut F,
+) where
+    F: FnMut(&JsonPath, bool),
+{
+    for (k, v) in source {
+        let name = if target.contains_key(&k) {
+            k
+        } else if key_match == KeyMatch::Exact {
+            continue;
+        } else {
+            match target.keys().find(|name| key_match.matches(&k, name)) {
+                Some(name) => name.clone(),
+                None => continue,
+            }
+        };
+        if let Some(target) = target.get_mut(&name) {
+            path.push_key(name);
+            deep_update_value(target, v, allow_null, key_match, path, f);
+            path.pop();
+        }
+    }
+}
+
+fn update_null(target: &mut Json, allow_null: bool) -> bool {
+    if allow_null {
+        *target = Json::Null;
+    }
+    true
+}
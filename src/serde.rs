@@ -50,6 +50,89 @@ where
     deserializer.deserialize_any(DeNumVisitor::<T>::default())
 }
 
+/// Wrapper to deserialize an integer or a string in options and sequences.
+#[cfg(feature = "num")]
+struct DeNum<T>(T);
+
+#[cfg(feature = "num")]
+impl<'de, T: Default + PrimInt + FromPrimitive + FromStr> de::Deserialize<'de> for DeNum<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        de_x_num(deserializer).map(DeNum)
+    }
+}
+
+/// Module to serialize an integer (e.g. an `i64` ID) to a decimal string,
+/// which is safe for JavaScript clients, and to deserialize it from an integer or a string.
+#[cfg(feature = "num")]
+pub mod serde_x_i64_str {
+    use super::*;
+
+    pub use super::de_x_num as deserialize;
+
+    /// Function to serializing an **`integer`** to a decimal string
+    pub fn serialize<S, T>(v: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+        T: fmt::Display,
+    {
+        serializer.collect_str(v)
+    }
+
+    /// Module to serialize and deserialize an **`Option<integer>`**.
+    pub mod option {
+        use super::*;
+
+        /// Function to serializing an **`Option<integer>`** to a decimal string or null
+        pub fn serialize<S, T>(v: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: ser::Serializer,
+            T: fmt::Display,
+        {
+            match v {
+                Some(v) => serializer.collect_str(v),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        /// Function to deserializing null, an integer or a string to an **`Option<integer>`**
+        pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+        where
+            D: de::Deserializer<'de>,
+            T: Default + PrimInt + FromPrimitive + FromStr,
+        {
+            let v: Option<DeNum<T>> = de::Deserialize::deserialize(deserializer)?;
+            Ok(v.map(|x| x.0))
+        }
+    }
+
+    /// Module to serialize and deserialize a **`Vec<integer>`**.
+    pub mod vec {
+        use super::*;
+
+        /// Function to serializing a **`Vec<integer>`** to an array of decimal strings
+        pub fn serialize<S, T>(v: &[T], serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: ser::Serializer,
+            T: fmt::Display,
+        {
+            serializer.collect_seq(v.iter().map(|x| x.to_string()))
+        }
+
+        /// Function to deserializing an array of integers or strings to a **`Vec<integer>`**
+        pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+        where
+            D: de::Deserializer<'de>,
+            T: Default + PrimInt + FromPrimitive + FromStr,
+        {
+            let v: Vec<DeNum<T>> = de::Deserialize::deserialize(deserializer)?;
+            Ok(v.into_iter().map(|x| x.0).collect())
+        }
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 
 #[cfg(feature = "num")]
@@ -106,6 +189,59 @@ where
     deserializer.deserialize_any(DeFloatVisitor::<T>::default())
 }
 
+#[cfg(feature = "rust_decimal")]
+struct DeDecimalVisitor;
+
+#[cfg(feature = "rust_decimal")]
+impl<'de> de::Visitor<'de> for DeDecimalVisitor {
+    type Value = rust_decimal::Decimal;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a decimal number or a string")
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Self::Value::try_from(v).map_err(|_| E::invalid_value(de::Unexpected::Float(v), &self))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(v.into())
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(v.into())
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let s = v.trim();
+        s.parse::<Self::Value>()
+            .or_else(|_| Self::Value::from_scientific(s))
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+    }
+}
+
+/// Function to deserializing a number or a string, including the scientific notation,
+/// to a **`Decimal`**
+#[cfg(feature = "rust_decimal")]
+pub fn de_x_decimal<'de, D>(deserializer: D) -> Result<rust_decimal::Decimal, D::Error>
+where
+    D: de::Deserializer<'de>,
+{
+    deserializer.deserialize_any(DeDecimalVisitor)
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 
 struct DeBoolVisitor;
@@ -209,6 +345,556 @@ pub mod serde_x_strings {
     pub use super::ser_x_strings as serialize;
 }
 
+////////////////////////////////////////////////////////////////////////////////
+
+/// Quoting of items in a [`StringList`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ListQuoting {
+    /// No quoting, an item must not contain the separator.
+    None,
+    /// A backslash escapes the next character, e.g. `a\,b`.
+    Backslash,
+    /// An item may be enclosed in double quotes, and `""` is a quote inside, e.g. `"a,""b"""`.
+    Csv,
+}
+
+/// Policy of empty items in a [`StringList`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ListEmpty {
+    Keep,
+    Skip,
+    Reject,
+}
+
+/// Format of a [`StringList`].
+pub trait ListFormat {
+    const SEPARATOR: char;
+    const QUOTING: ListQuoting = ListQuoting::None;
+    /// Trim unquoted whitespaces around items.
+    const TRIM: bool = true;
+    /// Policy of empty items which are not quoted.
+    const EMPTY: ListEmpty = ListEmpty::Skip;
+}
+
+/// Items separated with ',', without quoting.
+pub struct CommaList;
+
+impl ListFormat for CommaList {
+    const SEPARATOR: char = ',';
+}
+
+/// Items separated with ';', without quoting.
+pub struct SemicolonList;
+
+impl ListFormat for SemicolonList {
+    const SEPARATOR: char = ';';
+}
+
+/// Items in a CSV line, keeping empty items.
+pub struct CsvList;
+
+impl ListFormat for CsvList {
+    const SEPARATOR: char = ',';
+    const QUOTING: ListQuoting = ListQuoting::Csv;
+    const EMPTY: ListEmpty = ListEmpty::Keep;
+}
+
+/// Items separated with the character `C`, escaped with backslashes.
+pub struct CharList<const C: char>;
+
+impl<const C: char> ListFormat for CharList<C> {
+    const SEPARATOR: char = C;
+    const QUOTING: ListQuoting = ListQuoting::Backslash;
+}
+
+/// Adapter for `#[serde_as]` to serialize and deserialize a collection of `T` to/from a string
+/// in the format `F`, items are parsed with `FromStr` and formatted with `Display`.
+///
+/// # Examples
+///
+/// ```
+/// use xelf::prelude::*;
+///
+/// #[serde_as]
+/// #[derive(Deserialize, Serialize)]
+/// struct Data {
+///     #[serde_as(as = "StringList<CsvList>")]
+///     names: Vec<String>,
+///     #[serde_as(as = "StringList<CharList<'|'>, u32>")]
+///     ports: Vec<u32>,
+/// }
+///
+/// let a: Data = serde_json::from_value(json!({
+///     "names": r##"Tom, "Smith, John", """Q""""##,
+///     "ports": "80 | 443",
+/// }))
+/// .unwrap();
+/// assert_eq!(a.names, ["Tom", "Smith, John", "\"Q\""]);
+/// assert_eq!(a.ports, [80, 443]);
+/// assert_eq!(
+///     serde_json::to_value(&a).unwrap(),
+///     json!({"names": r##"Tom,"Smith, John","""Q""""##, "ports": "80|443"}),
+/// );
+/// ```
+pub struct StringList<F, T = String>(PhantomData<(F, T)>);
+
+impl<F: ListFormat, T> StringList<F, T> {
+    /// Split a string into items.
+    pub fn split<C>(s: &str) -> Result<C, String>
+    where
+        C: FromIterator<T>,
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        Self::split_raw(s)?
+            .into_iter()
+            .map(|x| x.parse::<T>().map_err(|e| format!("{:?}: {}", x, e)))
+            .collect()
+    }
+
+    /// Join items into a string, fail if an item can not be represented.
+    pub fn join<'a, I>(items: I) -> Result<String, String>
+    where
+        I: IntoIterator<Item = &'a T>,
+        T: 'a + fmt::Display,
+    {
+        let mut out = String::new();
+        for (i, item) in items.into_iter().enumerate() {
+            if i > 0 {
+                out.push(F::SEPARATOR);
+            }
+            let item = item.to_string();
+            let edge = |c: char| F::TRIM && c.is_whitespace();
+            match F::QUOTING {
+                ListQuoting::None => {
+                    if item.contains(F::SEPARATOR) {
+                        return Err(format!("{:?} contains the separator", item));
+                    }
+                    out.push_str(&item);
+                }
+                ListQuoting::Backslash => {
+                    let last = item.chars().count().saturating_sub(1);
+                    for (j, c) in item.chars().enumerate() {
+                        if c == F::SEPARATOR || c == '\\' || ((j == 0 || j == last) && edge(c)) {
+                            out.push('\\');
+                        }
+                        out.push(c);
+                    }
+                }
+                ListQuoting::Csv => {
+                    if item.is_empty()
+                        || item.contains([F::SEPARATOR, '"', '\n', '\r'])
+                        || item.starts_with(edge)
+                        || item.ends_with(edge)
+                    {
+                        out.push('"');
+                        out.push_str(&item.replace('"', "\"\""));
+                        out.push('"');
+                    } else {
+                        out.push_str(&item);
+                    }
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    fn split_raw(s: &str) -> Result<Vec<String>, String> {
+        let mut items = Vec::new();
+        if s.is_empty() {
+            return Ok(items);
+        }
+        let mut chars = s.chars();
+        loop {
+            let mut buf = String::new();
+            // The length of the quoted or escaped prefix which must not be trimmed.
+            let mut protected = 0;
+            let mut quoted = false;
+            let mut last = true;
+            while let Some(c) = chars.next() {
+                if c == F::SEPARATOR {
+                    last = false;
+                    break;
+                }
+                if quoted {
+                    if !(F::TRIM && c.is_whitespace()) {
+                        return Err(format!("unexpected {:?} after a quoted item", c));
+                    }
+                    continue;
+                }
+                if F::TRIM && buf.is_empty() && c.is_whitespace() {
+                    continue;
+                }
+                match F::QUOTING {
+                    ListQuoting::Backslash if c == '\\' => {
+                        buf.push(chars.next().ok_or("trailing backslash")?);
+                        protected = buf.len();
+                    }
+                    ListQuoting::Csv if c == '"' && buf.is_empty() => {
+                        loop {
+                            match chars.next().ok_or("unterminated quote")? {
+                                '"' if chars.as_str().starts_with('"') => {
+                                    chars.next();
+                                    buf.push('"');
+                                }
+                                '"' => break,
+                                c => buf.push(c),
+                            }
+                        }
+                        quoted = true;
+                        protected = buf.len();
+                    }
+                    _ => buf.push(c),
+                }
+            }
+            if F::TRIM {
+                while buf.len() > protected && buf.ends_with(char::is_whitespace) {
+                    buf.pop();
+                }
+            }
+            match F::EMPTY {
+                _ if quoted || !buf.is_empty() => items.push(buf),
+                ListEmpty::Keep => items.push(buf),
+                ListEmpty::Skip => (),
+                ListEmpty::Reject => return Err("empty item".to_owned()),
+            }
+            if last {
+                return Ok(items);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serde_with")]
+impl<'de, F, T, C> serde_with::DeserializeAs<'de, C> for StringList<F, T>
+where
+    F: ListFormat,
+    T: FromStr,
+    T::Err: fmt::Display,
+    C: FromIterator<T>,
+{
+    fn deserialize_as<D>(deserializer: D) -> Result<C, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let s = <std::borrow::Cow<str> as de::Deserialize>::deserialize(deserializer)?;
+        Self::split(&s).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(feature = "serde_with")]
+impl<F, T, C> serde_with::SerializeAs<C> for StringList<F, T>
+where
+    F: ListFormat,
+    T: fmt::Display,
+    for<'a> &'a C: IntoIterator<Item = &'a T>,
+{
+    fn serialize_as<S>(source: &C, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        let s = Self::join(source).map_err(ser::Error::custom)?;
+        serializer.serialize_str(&s)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "ipnetwork")]
+use ipnetwork::IpNetwork;
+
+#[cfg(feature = "ipnetwork")]
+struct DeIpNetworkVisitor;
+
+#[cfg(feature = "ipnetwork")]
+impl<'de> de::Visitor<'de> for DeIpNetworkVisitor {
+    type Value = IpNetwork;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an IP address or a CIDR block")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        v.trim()
+            .parse::<Self::Value>()
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+    }
+}
+
+/// Wrapper to deserialize an IP network in sequences.
+#[cfg(feature = "ipnetwork")]
+struct DeIpNetwork(IpNetwork);
+
+#[cfg(feature = "ipnetwork")]
+impl<'de> de::Deserialize<'de> for DeIpNetwork {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        de_x_ipnetwork(deserializer).map(DeIpNetwork)
+    }
+}
+
+#[cfg(feature = "ipnetwork")]
+struct DeIpNetworksVisitor;
+
+#[cfg(feature = "ipnetwork")]
+impl<'de> de::Visitor<'de> for DeIpNetworksVisitor {
+    type Value = Vec<IpNetwork>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an array of CIDR blocks or a string separated with comma")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        v.split(|x: char| [',', ';'].contains(&x) || x.is_whitespace())
+            .filter(|x| !x.is_empty())
+            .map(|x| DeIpNetworkVisitor.visit_str(x))
+            .collect()
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut v = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(x) = seq.next_element::<DeIpNetwork>()? {
+            v.push(x.0);
+        }
+        Ok(v)
+    }
+}
+
+/// Function to deserializing an IP address or a CIDR block string, surrounded by optional
+/// spaces, to an **`IpNetwork`**, a single address is a network with the full prefix length.
+#[cfg(feature = "ipnetwork")]
+pub fn de_x_ipnetwork<'de, D>(deserializer: D) -> Result<IpNetwork, D::Error>
+where
+    D: de::Deserializer<'de>,
+{
+    deserializer.deserialize_str(DeIpNetworkVisitor)
+}
+
+/// Module to serialize a **`Vec<IpNetwork>`** to an array of strings, and to deserialize it
+/// from an array or a string separated with ',', ';' or whitespaces.
+#[cfg(feature = "ipnetwork")]
+pub mod serde_x_ipnetworks {
+    use super::*;
+
+    /// Function to serializing a **`Vec<IpNetwork>`**
+    pub fn serialize<S>(v: &[IpNetwork], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.collect_seq(v.iter().map(|x| x.to_string()))
+    }
+
+    /// Function to deserializing a **`Vec<IpNetwork>`**
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<IpNetwork>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(DeIpNetworksVisitor)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Visitor to deserialize null, unit or an empty string to `None`, and others by the inner visitor.
+pub(crate) struct DeOptionVisitor<V>(pub(crate) V);
+
+impl<'de, V: de::Visitor<'de>> de::Visitor<'de> for DeOptionVisitor<V> {
+    type Value = Option<V::Value>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.0.expecting(formatter)?;
+        formatter.write_str(", null or an empty string")
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_bool(v).map(Some)
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_i64(v).map(Some)
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_u64(v).map(Some)
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_f64(v).map(Some)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        match v {
+            "" => Ok(None),
+            _ => self.0.visit_str(v).map(Some),
+        }
+    }
+}
+
+/// Implement `DeserializeAs` and `SerializeAs` of an adapter for the types and their options.
+#[cfg(feature = "serde_with")]
+macro_rules! impl_x_serde_as {
+    ($X:ty, $visitor:expr, $($T:ty),+) => {
+        $(
+            impl<'de> serde_with::DeserializeAs<'de, $T> for $X {
+                fn deserialize_as<D>(deserializer: D) -> Result<$T, D::Error>
+                where
+                    D: de::Deserializer<'de>,
+                {
+                    deserializer.deserialize_any($visitor)
+                }
+            }
+
+            impl<'de> serde_with::DeserializeAs<'de, Option<$T>> for $X {
+                fn deserialize_as<D>(deserializer: D) -> Result<Option<$T>, D::Error>
+                where
+                    D: de::Deserializer<'de>,
+                {
+                    deserializer.deserialize_any(DeOptionVisitor($visitor))
+                }
+            }
+
+            impl serde_with::SerializeAs<$T> for $X {
+                fn serialize_as<S>(v: &$T, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: ser::Serializer,
+                {
+                    ser::Serialize::serialize(v, serializer)
+                }
+            }
+
+            impl serde_with::SerializeAs<Option<$T>> for $X {
+                fn serialize_as<S>(v: &Option<$T>, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: ser::Serializer,
+                {
+                    ser::Serialize::serialize(v, serializer)
+                }
+            }
+        )+
+    };
+}
+
+/// Adapter for `#[serde_as]` to deserialize an integer or a string to an **`integer`**,
+/// like [`de_x_num`].
+///
+/// It also applies to `Option<integer>`, where null and an empty string are `None`,
+/// and composes inside `Vec`, `HashMap` and so on.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use xelf::prelude::*;
+///
+/// #[serde_as]
+/// #[derive(Deserialize)]
+/// struct Query {
+///     #[serde_as(as = "XNum")]
+///     page: Option<u32>,
+///     #[serde_as(as = "Vec<XNum>")]
+///     ids: Vec<i64>,
+///     #[serde_as(as = "HashMap<_, XNum>")]
+///     limits: HashMap<String, Option<i32>>,
+/// }
+///
+/// let q: Query = serde_json::from_value(json!({
+///     "page": "",
+///     "ids": [1, "2"],
+///     "limits": {"a": "3", "b": null},
+/// }))
+/// .unwrap();
+/// assert_eq!(q.page, None);
+/// assert_eq!(q.ids, [1, 2]);
+/// assert_eq!(q.limits["a"], Some(3));
+/// ```
+#[cfg(all(feature = "num", feature = "serde_with"))]
+pub struct XNum;
+
+#[cfg(all(feature = "num", feature = "serde_with"))]
+impl_x_serde_as!(
+    XNum,
+    DeNumVisitor::default(),
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize
+);
+
+/// Adapter for `#[serde_as]` to deserialize a number or a string to a **`float`**,
+/// like [`de_x_float`].
+///
+/// It also applies to `Option<float>`, where null and an empty string are `None`.
+#[cfg(all(feature = "num", feature = "serde_with"))]
+pub struct XFloat;
+
+#[cfg(all(feature = "num", feature = "serde_with"))]
+impl_x_serde_as!(XFloat, DeFloatVisitor::default(), f32, f64);
+
+/// Adapter for `#[serde_as]` to deserialize a boolean value, an integer (0/1) or
+/// a string ("true"/"false") to a **`bool`**, like [`de_x_bool`].
+///
+/// It also applies to `Option<bool>`, where null and an empty string are `None`.
+#[cfg(feature = "serde_with")]
+pub struct XBool;
+
+#[cfg(feature = "serde_with")]
+impl_x_serde_as!(XBool, DeBoolVisitor, bool);
+
 #[cfg(test)]
 mod tests {
     use crate::prelude::*;
@@ -255,4 +941,174 @@ mod tests {
 
         let _ = Data(true);
     }
+
+    #[test]
+    fn test_string_list() {
+        type Csv = StringList<CsvList>;
+        type Pipe = StringList<CharList<'|'>>;
+
+        let v: Vec<String> = Csv::split(r#" a , "b,c" ,, "" , "d""e" "#).unwrap();
+        assert_eq!(v, ["a", "b,c", "", "", "d\"e"]);
+        assert_eq!(Csv::join(&v).unwrap(), r#"a,"b,c","","","d""e""#);
+        assert!(Csv::split::<Vec<_>>(r#""a"b"#).is_err());
+        assert!(Csv::split::<Vec<_>>(r#""a"#).is_err());
+
+        let v: Vec<String> = Pipe::split(r"a\|b |\ c\ || d\\").unwrap();
+        assert_eq!(v, ["a|b", " c ", "d\\"]);
+        assert_eq!(Pipe::split::<Vec<_>>(&Pipe::join(&v).unwrap()).unwrap(), v);
+        assert!(Pipe::split::<Vec<_>>(r"a\").is_err());
+
+        let v: Vec<i32> = StringList::<SemicolonList, i32>::split("1; 2;;3 ").unwrap();
+        assert_eq!(v, [1, 2, 3]);
+        assert!(StringList::<SemicolonList, i32>::split::<Vec<_>>("1;x").is_err());
+        assert!(StringList::<CommaList>::join(&["a,b".to_owned()]).is_err());
+        let v: Vec<String> = StringList::<CommaList>::split("").unwrap();
+        assert!(v.is_empty());
+
+        struct Strict;
+        impl ListFormat for Strict {
+            const SEPARATOR: char = '/';
+            const TRIM: bool = false;
+            const EMPTY: ListEmpty = ListEmpty::Reject;
+        }
+        let v: BTreeSet<String> = StringList::<Strict>::split(" a/b ").unwrap();
+        assert_eq!(v, BTreeSet::from([" a".to_owned(), "b ".to_owned()]));
+        assert!(StringList::<Strict>::split::<Vec<_>>("a//b").is_err());
+    }
+
+    #[test]
+    fn test_lenient_types() {
+        #[derive(Debug, Deserialize, Serialize)]
+        struct Rule {
+            #[serde(with = "serde_x_ipnetworks")]
+            allow: Vec<ipnetwork::IpNetwork>,
+            #[serde(deserialize_with = "de_x_ipnetwork")]
+            gateway: ipnetwork::IpNetwork,
+            #[cfg(feature = "rust_decimal")]
+            #[serde(deserialize_with = "de_x_decimal")]
+            price: rust_decimal::Decimal,
+            #[cfg(feature = "datetime")]
+            #[serde(with = "serde_x_duration")]
+            timeout: Duration,
+        }
+
+        let a: Rule = serde_json::from_value(json!({
+            "allow": "10.0.0.0/8, 192.168.1.1;fe80::/10",
+            "gateway": " 10.0.0.1 ",
+            "price": "1.5e2",
+            "timeout": "PT1M30S",
+        }))
+        .unwrap();
+        assert_eq!(a.allow.len(), 3);
+        assert_eq!(a.allow[1].prefix(), 32);
+        assert_eq!(a.gateway.to_string(), "10.0.0.1/32");
+        #[cfg(feature = "rust_decimal")]
+        assert_eq!(a.price.to_string(), "150");
+        #[cfg(feature = "datetime")]
+        assert_eq!(a.timeout, Duration::from_secs(90));
+
+        let jsn = serde_json::to_value(&a).unwrap();
+        assert_eq!(jsn["allow"][2], "fe80::/10");
+        #[cfg(feature = "datetime")]
+        assert_eq!(jsn["timeout"], "1m 30s");
+        let b: Rule = serde_json::from_value(jsn).unwrap();
+        assert_eq!(a.allow, b.allow);
+
+        let b: Rule = serde_json::from_value(json!({
+            "allow": ["10.0.0.0/8"],
+            "gateway": "::1",
+            "price": 2.25,
+            "timeout": 1.5,
+        }))
+        .unwrap();
+        assert_eq!(b.allow[0].prefix(), 8);
+        #[cfg(feature = "rust_decimal")]
+        assert_eq!(b.price.to_string(), "2.25");
+        #[cfg(feature = "datetime")]
+        assert_eq!(b.timeout, Duration::from_millis(1500));
+        assert!(serde_json::from_value::<Rule>(json!({"allow": "10.0.0.0/33"})).is_err());
+    }
+
+    #[test]
+    fn test_x_adapters() {
+        use std::collections::HashMap;
+
+        #[serde_as]
+        #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+        struct Form {
+            #[serde_as(as = "XNum")]
+            age: u8,
+            #[serde_as(as = "XNum")]
+            #[serde(default)]
+            height: Option<i32>,
+            #[serde_as(as = "Option<XFloat>")]
+            #[serde(default)]
+            weight: Option<f32>,
+            #[serde_as(as = "Vec<XBool>")]
+            #[serde(default)]
+            flags: Vec<Option<bool>>,
+            #[serde_as(as = "HashMap<_, XFloat>")]
+            #[serde(default)]
+            scores: HashMap<String, f64>,
+        }
+
+        let a: Form = serde_json::from_value(json!({
+            "age": "18",
+            "height": "",
+            "weight": "60.5",
+            "flags": [1, "false", "", null, true],
+            "scores": {"math": "99.5", "art": 80},
+        }))
+        .unwrap();
+        assert_eq!(a.age, 18);
+        assert_eq!(a.height, None);
+        assert_eq!(a.weight, Some(60.5));
+        assert_eq!(a.flags, [Some(true), Some(false), None, None, Some(true)]);
+        assert_eq!(a.scores["math"], 99.5);
+        assert_eq!(a.scores["art"], 80.);
+
+        let b: Form = serde_json::from_value(serde_json::to_value(&a).unwrap()).unwrap();
+        assert_eq!(a, b);
+        let b: Form = serde_json::from_value(json!({"age": 1, "height": null})).unwrap();
+        assert_eq!((b.age, b.height), (1, None));
+        assert!(serde_json::from_value::<Form>(json!({"age": ""})).is_err());
+        assert!(serde_json::from_value::<Form>(json!({"age": 1, "height": "x"})).is_err());
+        assert!(serde_json::from_value::<Form>(json!({"age": 1, "flags": [2]})).is_err());
+    }
+
+    #[test]
+    fn test_i64_str() {
+        #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+        struct Ids {
+            #[serde(with = "serde_x_i64_str")]
+            id: i64,
+            #[serde(with = "serde_x_i64_str::option", default)]
+            parent_id: Option<i64>,
+            #[serde(with = "serde_x_i64_str::vec", default)]
+            children: Vec<u64>,
+        }
+
+        let a = Ids {
+            id: i64::MAX,
+            parent_id: Some(-1),
+            children: vec![u64::MAX, 0],
+        };
+        let jsn = serde_json::to_value(&a).unwrap();
+        assert_eq!(
+            jsn,
+            json!({
+                "id": "9223372036854775807",
+                "parent_id": "-1",
+                "children": ["18446744073709551615", "0"],
+            })
+        );
+        assert_eq!(serde_json::from_value::<Ids>(jsn).unwrap(), a);
+
+        let b: Ids = serde_json::from_value(json!({"id": 1, "children": [2, "3"]})).unwrap();
+        assert_eq!((b.id, b.parent_id, b.children), (1, None, vec![2, 3]));
+        let b: Ids = serde_json::from_value(json!({"id": "1", "parent_id": null})).unwrap();
+        assert_eq!(b.parent_id, None);
+        assert!(serde_json::from_value::<Ids>(json!({"id": "x"})).is_err());
+        assert!(serde_json::from_value::<Ids>(json!({"id": 1, "children": [-1]})).is_err());
+    }
 }
@@ -0,0 +1,301 @@
+//! Sortable string encodings of Snowflake IDs.
+//!
+//! IDs are encoded as fixed-width strings with alphabets in ASCII order,
+//! so that the strings of non-negative IDs sort the same as the IDs themselves.
+
+use super::SnowflakeError;
+
+/// String encodings of Snowflake IDs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SnowflakeEncoding {
+    /// Crockford's Base32 in 13 characters, decoded case-insensitively.
+    Base32,
+    /// Base62 in 11 characters, with digits, then upper and lower case letters.
+    Base62,
+}
+
+impl SnowflakeEncoding {
+    const BASE32_ALPHABET: &'static [u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+    const BASE62_ALPHABET: &'static [u8; 62] =
+        b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+    /// Get the number of characters of an encoded ID.
+    #[inline]
+    pub const fn width(self) -> usize {
+        match self {
+            Self::Base32 => 13,
+            Self::Base62 => 11,
+        }
+    }
+
+    #[inline]
+    const fn radix(self) -> u64 {
+        match self {
+            Self::Base32 => 32,
+            Self::Base62 => 62,
+        }
+    }
+
+    /// Encode an ID into a fixed-width string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the ID is negative, use [`SnowflakeEncoding::try_encode`] to get an error instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xelf::snowflake::*;
+    ///
+    /// let s = SnowflakeEncoding::Base32.encode(1_234_567_890);
+    /// assert_eq!(s, "00000014SC0PJ");
+    /// assert_eq!(SnowflakeEncoding::Base32.decode(&s.to_lowercase()), Ok(1_234_567_890));
+    /// assert!(SnowflakeEncoding::Base62.encode(9) < SnowflakeEncoding::Base62.encode(10));
+    /// ```
+    pub fn encode(self, id: i64) -> String {
+        match self.try_encode(id) {
+            Ok(v) => v,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Encode an ID into a fixed-width string, fail if the ID is negative.
+    pub fn try_encode(self, id: i64) -> Result<String, SnowflakeError> {
+        if id < 0 {
+            return Err(SnowflakeError::NegativeId(id));
+        }
+        let alphabet: &[u8] = match self {
+            Self::Base32 => Self::BASE32_ALPHABET,
+            Self::Base62 => Self::BASE62_ALPHABET,
+        };
+        let mut buf = [b'0'; 13];
+        let buf = &mut buf[..self.width()];
+        let mut v = id as u64;
+        for c in buf.iter_mut().rev() {
+            *c = alphabet[(v % self.radix()) as usize];
+            v /= self.radix();
+        }
+        Ok(buf.iter().map(|&c| c as char).collect())
+    }
+
+    /// Decode an ID from a string, which must have exactly the width of the encoding
+    /// and a value in the range of non-negative `i64`.
+    pub fn decode(self, s: &str) -> Result<i64, SnowflakeError> {
+        let invalid = || SnowflakeError::InvalidEncoding(s.to_owned());
+        if s.len() != self.width() {
+            return Err(invalid());
+        }
+        let mut v = 0u64;
+        for c in s.bytes() {
+            let digit = match self {
+                Self::Base32 => Self::base32_digit(c),
+                Self::Base62 => Self::base62_digit(c),
+            }
+            .ok_or_else(invalid)?;
+            v = v
+                .checked_mul(self.radix())
+                .and_then(|v| v.checked_add(digit))
+                .ok_or_else(invalid)?;
+        }
+        i64::try_from(v).map_err(|_| invalid())
+    }
+
+    fn base32_digit(c: u8) -> Option<u64> {
+        let c = match c.to_ascii_uppercase() {
+            b'O' => b'0',
+            b'I' | b'L' => b'1',
+            c => c,
+        };
+        Self::BASE32_ALPHABET
+            .iter()
+            .position(|&x| x == c)
+            .map(|x| x as u64)
+    }
+
+    fn base62_digit(c: u8) -> Option<u64> {
+        match c {
+            b'0'..=b'9' => Some((c - b'0') as u64),
+            b'A'..=b'Z' => Some((c - b'A') as u64 + 10),
+            b'a'..=b'z' => Some((c - b'a') as u64 + 36),
+            _ => None,
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Serialize an `i64` Snowflake ID as a Crockford's Base32 string.
+#[cfg(feature = "serde")]
+pub mod serde_x_snowflake_base32 {
+    use super::*;
+    use ::serde::{
+        de,
+        ser::{self, Serializer},
+    };
+    use ::std::fmt;
+
+    pub(super) struct DeSnowflakeVisitor(pub(super) SnowflakeEncoding);
+
+    impl<'de> de::Visitor<'de> for DeSnowflakeVisitor {
+        type Value = i64;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "an encoded Snowflake ID or an integer")
+        }
+
+        fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            if v < 0 {
+                return Err(E::invalid_value(de::Unexpected::Signed(v), &self));
+            }
+            Ok(v)
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            i64::try_from(v).map_err(|_| E::invalid_value(de::Unexpected::Unsigned(v), &self))
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            self.0
+                .decode(v)
+                .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+        }
+    }
+
+    /// Function to serializing a Snowflake ID
+    pub fn serialize<S>(id: &i64, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let s = SnowflakeEncoding::Base32
+            .try_encode(*id)
+            .map_err(ser::Error::custom)?;
+        serializer.serialize_str(&s)
+    }
+
+    /// Function to deserializing a Snowflake ID
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<i64, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(DeSnowflakeVisitor(SnowflakeEncoding::Base32))
+    }
+}
+
+/// Serialize an `i64` Snowflake ID as a Base62 string.
+#[cfg(feature = "serde")]
+pub mod serde_x_snowflake_base62 {
+    use super::serde_x_snowflake_base32::DeSnowflakeVisitor;
+    use super::*;
+    use ::serde::{
+        de,
+        ser::{self, Serializer},
+    };
+
+    /// Function to serializing a Snowflake ID
+    pub fn serialize<S>(id: &i64, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let s = SnowflakeEncoding::Base62
+            .try_encode(*id)
+            .map_err(ser::Error::custom)?;
+        serializer.serialize_str(&s)
+    }
+
+    /// Function to deserializing a Snowflake ID
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<i64, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(DeSnowflakeVisitor(SnowflakeEncoding::Base62))
+    }
+}
+
+#[cfg(feature = "serde")]
+pub use serde_x_snowflake_base32::{
+    deserialize as de_x_snowflake_base32, serialize as ser_x_snowflake_base32,
+};
+#[cfg(feature = "serde")]
+pub use serde_x_snowflake_base62::{
+    deserialize as de_x_snowflake_base62, serialize as ser_x_snowflake_base62,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snowflake_encoding() {
+        for enc in [SnowflakeEncoding::Base32, SnowflakeEncoding::Base62] {
+            let ids = [0, 1, 31, 32, 61, 62, 1 << 40, i64::MAX - 1, i64::MAX];
+            let strs: Vec<String> = ids.iter().map(|&id| enc.encode(id)).collect();
+            assert!(strs.iter().all(|s| s.len() == enc.width()));
+            assert!(strs.windows(2).all(|x| x[0] < x[1]));
+            for (s, &id) in strs.iter().zip(ids.iter()) {
+                assert_eq!(enc.decode(s), Ok(id));
+            }
+            assert_eq!(enc.try_encode(-1), Err(SnowflakeError::NegativeId(-1)));
+            assert!(enc.decode("0").is_err());
+            assert!(enc.decode("zzzzzzzzzzzzz").is_err());
+        }
+        assert_eq!(SnowflakeEncoding::Base62.encode(i64::MAX), "AzL8n0Y58m7");
+        assert_eq!(
+            SnowflakeEncoding::Base32.decode("0O0IL00000000"),
+            Ok(33 << 40)
+        );
+        assert!(SnowflakeEncoding::Base32.decode("000000000000U").is_err());
+        assert!(SnowflakeEncoding::Base62.decode("000000000-0").is_err());
+        // Values beyond `i64::MAX` do not wrap to negative IDs.
+        assert!(SnowflakeEncoding::Base32.decode("8000000000000").is_err());
+        assert!(SnowflakeEncoding::Base32.decode("FZZZZZZZZZZZZ").is_err());
+        assert!(SnowflakeEncoding::Base62.decode("AzL8n0Y58m8").is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_snowflake_encoding_serde() {
+        use ::serde::{Deserialize, Serialize};
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Model {
+            #[serde(with = "serde_x_snowflake_base32")]
+            id: i64,
+            #[serde(with = "serde_x_snowflake_base62")]
+            parent_id: i64,
+        }
+
+        let m = Model {
+            id: 1_234_567_890,
+            parent_id: 62,
+        };
+        let jsn = serde_json::to_value(&m).unwrap();
+        assert_eq!(
+            jsn,
+            serde_json::json!({"id": "00000014SC0PJ", "parent_id": "00000000010"})
+        );
+        assert_eq!(serde_json::from_value::<Model>(jsn).unwrap(), m);
+        let jsn = serde_json::json!({"id": 1_234_567_890, "parent_id": "00000000010"});
+        assert_eq!(serde_json::from_value::<Model>(jsn).unwrap(), m);
+        assert!(
+            serde_json::from_value::<Model>(serde_json::json!({"id": "?", "parent_id": 0}))
+                .is_err()
+        );
+        assert!(
+            serde_json::from_value::<Model>(serde_json::json!({"id": -1, "parent_id": 0})).is_err()
+        );
+        assert!(serde_json::to_value(Model {
+            id: -1,
+            parent_id: 0
+        })
+        .is_err());
+    }
+}
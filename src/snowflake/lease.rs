@@ -0,0 +1,451 @@
+//! Lease worker IDs of Snowflake generators from a database table.
+//!
+//! Each process claims a free worker slot with a TTL, renews it in the background
+//! with [`SnowflakeLeaseService`], and releases it on shutdown.
+
+use super::{Snowflake, SnowflakeError, SnowflakeLayout};
+use crate::db::*;
+use crate::esvc::*;
+use crate::prelude::*;
+
+/// Options to lease worker IDs from a database table.
+#[derive(Clone, Debug)]
+pub struct SnowflakeLeaseOptions {
+    table: String,
+    owner: String,
+    ttl: Duration,
+    renew_interval: Duration,
+    datacenter_id: i64,
+    max_worker_id: i64,
+}
+
+impl Default for SnowflakeLeaseOptions {
+    fn default() -> Self {
+        Self {
+            table: "t_snowflake_lease".to_owned(),
+            owner: format!("{}-{}", std::process::id(), UnixTimeMicros::micros_now()),
+            ttl: Duration::from_secs(60),
+            renew_interval: Duration::from_secs(20),
+            datacenter_id: 0,
+            max_worker_id: SnowflakeLayout::STANDARD.max_worker_id(),
+        }
+    }
+}
+
+impl SnowflakeLeaseOptions {
+    /// Create options with the standard layout, a TTL of 60 seconds and a renewal
+    /// interval of 20 seconds.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the name of the lease table, `t_snowflake_lease` by default.
+    ///
+    /// It's an identifier of ASCII letters, digits and `_`, optionally qualified by a schema,
+    /// otherwise the operations on the table fail.
+    pub fn with_table<T: Into<String>>(mut self, table: T) -> Self {
+        self.table = table.into();
+        self
+    }
+
+    /// Set the unique name of this process, it's generated from the process ID by default.
+    pub fn with_owner<T: Into<String>>(mut self, owner: T) -> Self {
+        self.owner = owner.into();
+        self
+    }
+
+    /// Set the time to live of a lease.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Set the interval to renew a lease, it should be much shorter than the TTL.
+    pub fn with_renew_interval(mut self, interval: Duration) -> Self {
+        self.renew_interval = interval;
+        self
+    }
+
+    /// Set the datacenter ID in which to lease a worker ID.
+    pub fn with_datacenter_id(mut self, datacenter_id: i64) -> Self {
+        self.datacenter_id = datacenter_id;
+        self
+    }
+
+    /// Set the range of worker IDs by the layout of generators.
+    pub fn with_layout(mut self, layout: &SnowflakeLayout) -> Self {
+        self.max_worker_id = layout.max_worker_id();
+        self
+    }
+
+    #[inline]
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    #[inline]
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+
+    /// Create the lease table if it does not exist.
+    pub async fn create_table<C>(&self, db: &C) -> DbResult<()>
+    where
+        C: DbConnectionTrait,
+    {
+        self.check_table()?;
+        let sql = format!(
+            "CREATE TABLE IF NOT EXISTS {} (\
+            datacenter_id INTEGER NOT NULL, \
+            worker_id INTEGER NOT NULL, \
+            owner VARCHAR(255) NOT NULL, \
+            expires_at BIGINT NOT NULL, \
+            PRIMARY KEY (datacenter_id, worker_id))",
+            self.table
+        );
+        db.execute_unprepared(&sql).await?;
+        Ok(())
+    }
+
+    /// Claim a free or expired worker slot.
+    pub async fn acquire<C>(&self, db: &C) -> DbResult<SnowflakeLease>
+    where
+        C: DbConnectionTrait,
+    {
+        self.check_backend(db)?;
+        let slots = self.max_worker_id + 1;
+        // Start from different slots to reduce conflicts between processes.
+        let start = std::process::id() as i64 % slots;
+        for i in 0..slots {
+            let worker_id = (start + i) % slots;
+            let now = UnixTimeMicros::micros_now();
+            let expires_at = now + self.ttl.as_micros() as i64;
+            let sql = self.acquire_sql(db.backend())?;
+            let values: [Value; 5] = [
+                self.datacenter_id.into(),
+                worker_id.into(),
+                self.owner.clone().into(),
+                expires_at.into(),
+                now.into(),
+            ];
+            if self.execute(db, sql, values).await? == 1 {
+                return Ok(SnowflakeLease {
+                    datacenter_id: self.datacenter_id,
+                    worker_id,
+                    owner: self.owner.clone(),
+                    expires_at,
+                });
+            }
+        }
+        Err(DbErr::Custom(format!(
+            "no free Snowflake worker ID in datacenter {}",
+            self.datacenter_id
+        )))
+    }
+
+    /// Extend the expiration time of a lease, returns `false` if the lease is lost or expired,
+    /// then it must be acquired again.
+    pub async fn renew<C>(&self, db: &C, lease: &mut SnowflakeLease) -> DbResult<bool>
+    where
+        C: DbConnectionTrait,
+    {
+        self.check_backend(db)?;
+        let now = UnixTimeMicros::micros_now();
+        let expires_at = now + self.ttl.as_micros() as i64;
+        let sql = format!(
+            "UPDATE {} SET expires_at = ? \
+            WHERE datacenter_id = ? AND worker_id = ? AND owner = ? AND expires_at >= ?",
+            self.table
+        );
+        let values: [Value; 5] = [
+            expires_at.into(),
+            lease.datacenter_id.into(),
+            lease.worker_id.into(),
+            lease.owner.clone().into(),
+            now.into(),
+        ];
+        if self.execute(db, sql, values).await? == 1 {
+            lease.expires_at = expires_at;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Give back a lease, returns `false` if the lease has been lost.
+    pub async fn release<C>(&self, db: &C, lease: &SnowflakeLease) -> DbResult<bool>
+    where
+        C: DbConnectionTrait,
+    {
+        self.check_backend(db)?;
+        let sql = format!(
+            "DELETE FROM {} WHERE datacenter_id = ? AND worker_id = ? AND owner = ?",
+            self.table
+        );
+        let values: [Value; 3] = [
+            lease.datacenter_id.into(),
+            lease.worker_id.into(),
+            lease.owner.clone().into(),
+        ];
+        Ok(self.execute(db, sql, values).await? == 1)
+    }
+
+    /// Get the SQL to take a slot if it's free or expired.
+    fn acquire_sql(&self, backend: DbBackend) -> DbResult<String> {
+        // Postgres refers to the target table by an alias, which SQLite does not need.
+        let (alias, target) = match backend {
+            DbBackend::Postgres => (" AS l", "l"),
+            DbBackend::Sqlite => ("", self.table.as_str()),
+            _ => return Err(DbErr::Custom("no implementation".to_owned())),
+        };
+        Ok(format!(
+            "INSERT INTO {}{} (datacenter_id, worker_id, owner, expires_at) VALUES (?, ?, ?, ?) \
+            ON CONFLICT (datacenter_id, worker_id) DO UPDATE \
+            SET owner = excluded.owner, expires_at = excluded.expires_at \
+            WHERE {}.expires_at < ?",
+            self.table, alias, target
+        ))
+    }
+
+    fn check_backend<C>(&self, db: &C) -> DbResult<()>
+    where
+        C: DbConnectionTrait,
+    {
+        self.check_table()?;
+        match db.backend() {
+            DbBackend::Postgres | DbBackend::Sqlite => Ok(()),
+            _ => Err(DbErr::Custom("no implementation".to_owned())),
+        }
+    }
+
+    /// Check that the table name is a plain identifier, since it's put in SQL as it is.
+    fn check_table(&self) -> DbResult<()> {
+        let valid = self.table.split('.').all(|x| {
+            x.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                && x.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        });
+        if valid && self.table.split('.').count() <= 2 {
+            Ok(())
+        } else {
+            Err(DbErr::Custom(format!(
+                "invalid Snowflake lease table {:?}",
+                self.table
+            )))
+        }
+    }
+
+    async fn execute<C, const N: usize>(
+        &self,
+        db: &C,
+        sql: String,
+        values: [Value; N],
+    ) -> DbResult<u64>
+    where
+        C: DbConnectionTrait,
+    {
+        let backend = db.backend();
+        let sql = _db_cust_with_values(backend, sql).into_owned();
+        let result = db
+            .execute_raw(Statement::from_sql_and_values(backend, sql, values))
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+/// A worker slot leased from the database.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SnowflakeLease {
+    pub datacenter_id: i64,
+    pub worker_id: i64,
+    pub owner: String,
+    /// The expiration time in UNIX microseconds.
+    pub expires_at: UnixTimeMicros,
+}
+
+impl SnowflakeLease {
+    /// Create a generator with the leased IDs.
+    pub fn snowflake(
+        &self,
+        layout: SnowflakeLayout,
+        epoch: i64,
+    ) -> Result<Snowflake, SnowflakeError> {
+        Snowflake::with_layout(layout, epoch, self.worker_id, self.datacenter_id)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+easy_service!(ASYNC
+    pub SnowflakeLeaseService,
+    SnowflakeLeaseTask,
+    pub SnowflakeLeaseServiceInner<C> {
+        lost: Arc<AtomicBool>,
+    }
+    where C: DbConnectionTrait + Send + Sync + 'static
+);
+
+/// The background task of [`SnowflakeLeaseService`].
+pub struct SnowflakeLeaseTask<C> {
+    db: C,
+    options: SnowflakeLeaseOptions,
+    lease: SnowflakeLease,
+}
+
+impl<C> SnowflakeLeaseService<C>
+where
+    C: DbConnectionTrait + Send + Sync + 'static,
+{
+    /// Create a service which renews the lease periodically and releases it on termination.
+    pub fn new(db: C, options: SnowflakeLeaseOptions, lease: SnowflakeLease) -> Self {
+        let task = Box::new(SnowflakeLeaseTask { db, options, lease });
+        Self(easy_service!(SnowflakeLeaseServiceInner {
+            token: CancellationToken::new(),
+            task: easy_service!(TASK task),
+            lost: Arc::new(AtomicBool::new(false)),
+        }))
+    }
+
+    /// Check if the lease failed to renew and may have been taken by another process.
+    ///
+    /// IDs must not be generated with a lost lease.
+    #[inline]
+    pub fn is_lost(&self) -> bool {
+        self.0.lost.load(Ordering::Acquire)
+    }
+}
+
+impl<C> SnowflakeLeaseServiceInner<C>
+where
+    C: DbConnectionTrait + Send + Sync + 'static,
+{
+    fn run(&self, task: Box<SnowflakeLeaseTask<C>>) -> io::Result<tokio::task::JoinHandle<()>> {
+        let token = self.token.clone();
+        let lost = self.lost.clone();
+        Ok(tokio::spawn(async move {
+            let SnowflakeLeaseTask {
+                db,
+                options,
+                mut lease,
+            } = *task;
+            loop {
+                tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = tokio::time::sleep(options.renew_interval) => (),
+                }
+                match options.renew(&db, &mut lease).await {
+                    Ok(true) => (),
+                    Ok(false) => {
+                        lost.store(true, Ordering::Release);
+                        return;
+                    }
+                    // Retry on the next interval if it's before the lease expires, or give it up
+                    // now, since others may take the slot once it expires.
+                    Err(_) => {
+                        let remaining = lease.expires_at - UnixTimeMicros::micros_now();
+                        if remaining <= options.renew_interval.as_micros() as i64 {
+                            lost.store(true, Ordering::Release);
+                            return;
+                        }
+                    }
+                }
+            }
+            ok!(options.release(&db, &lease).await);
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_snowflake_lease() {
+        let mut opts = ConnectOptions::new("sqlite::memory:");
+        opts.max_connections(1);
+        let db = Database::connect(opts).await.unwrap();
+
+        let options = SnowflakeLeaseOptions::new()
+            .with_datacenter_id(3)
+            .with_layout(&SnowflakeLayout::new().with_worker_bits(1));
+        options.create_table(&db).await.unwrap();
+        options.create_table(&db).await.unwrap();
+
+        let mut a = options.clone().with_owner("A").acquire(&db).await.unwrap();
+        let b = options.clone().with_owner("B").acquire(&db).await.unwrap();
+        assert_ne!(a.worker_id, b.worker_id);
+        assert_eq!(a.datacenter_id, 3);
+        assert!(options.acquire(&db).await.is_err());
+
+        assert!(options.renew(&db, &mut a).await.unwrap());
+        assert!(options.release(&db, &a).await.unwrap());
+        assert!(!options.renew(&db, &mut a).await.unwrap());
+        let c = options.clone().with_owner("C").acquire(&db).await.unwrap();
+        assert_eq!(c.worker_id, a.worker_id);
+        let sf = c.snowflake(SnowflakeLayout::STANDARD, 0).unwrap();
+        assert_eq!(sf.decode(sf.generate()).worker_id, c.worker_id);
+
+        // An expired lease can be taken by others.
+        let d = options.clone().with_owner("D");
+        assert!(d.acquire(&db).await.is_err());
+        let expired = options.clone().with_ttl(Duration::ZERO);
+        assert!(expired.renew(&db, &mut c.clone()).await.unwrap());
+        tokio::time::sleep(Duration::from_millis(2)).await;
+        assert!(!options.renew(&db, &mut c.clone()).await.unwrap());
+        assert_eq!(d.acquire(&db).await.unwrap().worker_id, c.worker_id);
+
+        // The service releases the lease on termination.
+        let options = options.with_renew_interval(Duration::from_millis(1));
+        let e = options.clone().with_owner("E").acquire(&db).await;
+        assert!(e.is_err());
+        let svc = SnowflakeLeaseService::new(db.clone(), options.clone().with_owner("B"), b);
+        svc.start().unwrap();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!svc.is_lost());
+        svc.join().await;
+        let e = options.clone().with_owner("E").acquire(&db).await.unwrap();
+        assert_eq!(e.owner, "E");
+
+        // The service gives up a lease which can't be renewed before it expires.
+        let missing = options
+            .clone()
+            .with_table("t_missing")
+            .with_renew_interval(Duration::from_millis(50));
+        let lease = SnowflakeLease {
+            expires_at: UnixTimeMicros::micros_now() + 80_000,
+            ..e.clone()
+        };
+        let svc = SnowflakeLeaseService::new(db.clone(), missing, lease);
+        svc.start().unwrap();
+        tokio::time::sleep(Duration::from_millis(65)).await;
+        assert!(svc.is_lost());
+        svc.join().await;
+
+        let invalid = options
+            .clone()
+            .with_table("t; DROP TABLE t_snowflake_lease");
+        assert!(invalid.create_table(&db).await.is_err());
+        assert!(invalid.acquire(&db).await.is_err());
+        assert!(options
+            .clone()
+            .with_table("main.t_lease")
+            .check_table()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_snowflake_lease_sql() {
+        let options = SnowflakeLeaseOptions::new();
+        let sql = options.acquire_sql(DbBackend::Postgres).unwrap();
+        assert_eq!(
+            _db_cust_with_values(DbBackend::Postgres, sql),
+            "INSERT INTO t_snowflake_lease AS l (datacenter_id, worker_id, owner, expires_at) \
+            VALUES ($1, $2, $3, $4) ON CONFLICT (datacenter_id, worker_id) DO UPDATE \
+            SET owner = excluded.owner, expires_at = excluded.expires_at \
+            WHERE l.expires_at < $5"
+        );
+        let sql = options.acquire_sql(DbBackend::Sqlite).unwrap();
+        assert!(sql.ends_with("WHERE t_snowflake_lease.expires_at < ?"));
+        assert!(options.acquire_sql(DbBackend::MySql).is_err());
+    }
+}
@@ -0,0 +1,1131 @@
+mod codec;
+#[cfg(feature = "db")]
+mod lease;
+
+pub use codec::*;
+#[cfg(feature = "db")]
+pub use lease::*;
+
+#[cfg(feature = "datetime")]
+use crate::datetime::{utc_default, DateTimeUtc};
+#[cfg(feature = "datetime")]
+use chrono::TimeZone;
+use std::fmt;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Snowflake ID generator, reading the time from the system clock by default.
+pub struct Snowflake<T: TimeSource = SystemClock> {
+    pub epoch: i64,
+    worker_id: i64,
+    datacenter_id: i64,
+    layout: SnowflakeLayout,
+    clock_policy: ClockRegressionPolicy,
+    time_source: T,
+    id: AtomicI64,
+    /// The latest clock time in ticks observed by the generator.
+    clock: AtomicI64,
+    clock_regressions: AtomicU64,
+    sequence_exhaustions: AtomicU64,
+}
+
+impl<T: TimeSource + Default> Default for Snowflake<T> {
+    fn default() -> Self {
+        Self {
+            epoch: Snowflake::EPOCH_2021,
+            worker_id: 0,
+            datacenter_id: 0,
+            layout: Default::default(),
+            clock_policy: Default::default(),
+            time_source: Default::default(),
+            id: Default::default(),
+            clock: Default::default(),
+            clock_regressions: Default::default(),
+            sequence_exhaustions: Default::default(),
+        }
+    }
+}
+
+impl Snowflake {
+    pub const EPOCH_2021: i64 = 1_627_588_000_000;
+    pub const EPOCH_2022: i64 = 1_640_995_200_000;
+    pub const EPOCH_2026: i64 = 1_767_225_600_000;
+
+    /// Get milliseconds duration since the Unix Epoch.
+    #[inline]
+    pub fn now_millis() -> i64 {
+        SystemClock.now_millis()
+    }
+
+    /// Create a generator with the standard layout.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `worker_id` or `datacenter_id` does not fit in its field,
+    /// use [`Snowflake::try_new`] to get an error instead.
+    pub fn new(epoch: i64, worker_id: i64, datacenter_id: i64) -> Self {
+        match Self::try_new(epoch, worker_id, datacenter_id) {
+            Ok(v) => v,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Create a generator with the standard layout.
+    #[inline]
+    pub fn try_new(epoch: i64, worker_id: i64, datacenter_id: i64) -> Result<Self, SnowflakeError> {
+        Self::with_layout(SnowflakeLayout::STANDARD, epoch, worker_id, datacenter_id)
+    }
+
+    /// Create a generator with a custom layout.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xelf::snowflake::*;
+    ///
+    /// let layout = SnowflakeLayout::new()
+    ///     .with_timestamp_bits(39)
+    ///     .with_worker_bits(8)
+    ///     .with_datacenter_bits(0)
+    ///     .with_sequence_bits(16)
+    ///     .with_time_unit(SnowflakeTimeUnit::Millis10);
+    /// let sf = Snowflake::with_layout(layout, Snowflake::EPOCH_2026, 200, 0).unwrap();
+    /// assert_eq!(sf.decode(sf.generate()).worker_id, 200);
+    ///
+    /// assert!(Snowflake::with_layout(layout, Snowflake::EPOCH_2026, 256, 0).is_err());
+    /// ```
+    pub fn with_layout(
+        layout: SnowflakeLayout,
+        epoch: i64,
+        worker_id: i64,
+        datacenter_id: i64,
+    ) -> Result<Self, SnowflakeError> {
+        layout.validate()?;
+        if !(0..=layout.max_worker_id()).contains(&worker_id) {
+            return Err(SnowflakeError::WorkerIdOutOfRange(worker_id));
+        }
+        if !(0..=layout.max_datacenter_id()).contains(&datacenter_id) {
+            return Err(SnowflakeError::DatacenterIdOutOfRange(datacenter_id));
+        }
+        Ok(Self {
+            epoch,
+            worker_id,
+            datacenter_id,
+            layout,
+            ..Default::default()
+        })
+    }
+}
+
+impl<T: TimeSource> Snowflake<T> {
+    /// Set the policy to handle the wall clock moving backwards.
+    pub fn with_clock_policy(mut self, policy: ClockRegressionPolicy) -> Self {
+        self.clock_policy = policy;
+        self
+    }
+
+    /// Replace the time source, the state of generation is reset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xelf::snowflake::*;
+    ///
+    /// let sf = Snowflake::new(0, 1, 1).with_time_source(MockClock::new(1000));
+    /// assert_eq!(sf.decode(sf.generate()).timestamp, 1000);
+    /// sf.time_source().advance(5);
+    /// assert_eq!(sf.decode(sf.generate()).timestamp, 1005);
+    /// ```
+    pub fn with_time_source<U: TimeSource>(self, time_source: U) -> Snowflake<U> {
+        Snowflake {
+            epoch: self.epoch,
+            worker_id: self.worker_id,
+            datacenter_id: self.datacenter_id,
+            layout: self.layout,
+            clock_policy: self.clock_policy,
+            time_source,
+            id: Default::default(),
+            clock: Default::default(),
+            clock_regressions: Default::default(),
+            sequence_exhaustions: Default::default(),
+        }
+    }
+
+    /// Get the time source.
+    #[inline]
+    pub fn time_source(&self) -> &T {
+        &self.time_source
+    }
+
+    /// Get the worker ID, which is validated against the layout.
+    #[inline]
+    pub fn worker_id(&self) -> i64 {
+        self.worker_id
+    }
+
+    /// Get the datacenter ID, which is validated against the layout.
+    #[inline]
+    pub fn datacenter_id(&self) -> i64 {
+        self.datacenter_id
+    }
+
+    /// Get the bit layout of IDs.
+    #[inline]
+    pub fn layout(&self) -> &SnowflakeLayout {
+        &self.layout
+    }
+
+    /// Get the policy to handle the wall clock moving backwards.
+    #[inline]
+    pub fn clock_policy(&self) -> ClockRegressionPolicy {
+        self.clock_policy
+    }
+
+    /// Get the counters of abnormal events since the generator was created.
+    pub fn stats(&self) -> SnowflakeStats {
+        SnowflakeStats {
+            clock_regressions: self.clock_regressions.load(Ordering::Relaxed),
+            sequence_exhaustions: self.sequence_exhaustions.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Get the milliseconds duration since this epoch.
+    #[inline]
+    pub fn millis_since_epoch(&self) -> i64 {
+        self.time_source.now_millis() - self.epoch
+    }
+
+    /// Generate a new ID.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the clock moved backwards and the policy refuses to generate,
+    /// or the timestamp is out of range of the layout,
+    /// use [`Snowflake::try_generate`] to get an error instead.
+    #[inline]
+    pub fn generate(&self) -> i64 {
+        self.generate_with_time(self.millis_since_epoch())
+    }
+
+    /// Generate a new ID, fail if the clock moved backwards and the policy refuses to generate,
+    /// or the timestamp is out of range of the layout.
+    #[inline]
+    pub fn try_generate(&self) -> Result<i64, SnowflakeError> {
+        self.try_generate_with_time(self.millis_since_epoch())
+    }
+
+    pub fn generate_with(&self, time_millis: i64, seq: i64) -> i64 {
+        self.layout
+            .compose(self.layout.ticks(time_millis), self.node_bits(), seq)
+    }
+
+    /// Generate a new ID with the milliseconds duration since this epoch.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the clock moved backwards and the policy refuses to generate,
+    /// or the timestamp is out of range of the layout,
+    /// use [`Snowflake::try_generate_with_time`] to get an error instead.
+    pub fn generate_with_time(&self, time_millis: i64) -> i64 {
+        match self.try_generate_with_time(time_millis) {
+            Ok(v) => v,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Generate a new ID with the milliseconds duration since this epoch,
+    /// fail if the clock moved backwards and the policy refuses to generate,
+    /// or the timestamp is out of range of the layout.
+    pub fn try_generate_with_time(&self, time_millis: i64) -> Result<i64, SnowflakeError> {
+        let mut ts = self.layout.ticks(time_millis);
+        if let Some(wait) = self.check_clock(&mut ts)? {
+            self.time_source.sleep(wait);
+        }
+        let (ts, seq) = self.reserve(ts, 1, true)?.unwrap_or_default();
+        Ok(self.layout.compose(ts, self.node_bits(), seq))
+    }
+
+    /// Generate `n` IDs at once, reserving a contiguous range of sequences in one atomic step.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is too large, the clock moved backwards and the policy refuses to generate,
+    /// or the timestamp is out of range of the layout,
+    /// use [`Snowflake::try_generate_batch`] to get an error instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use xelf::snowflake::*;
+    ///
+    /// let sf = Snowflake::new(Snowflake::EPOCH_2026, 1, 1);
+    /// let ids: Vec<i64> = sf.generate_batch(10_000).collect();
+    /// assert_eq!(ids.len(), 10_000);
+    /// assert!(ids.windows(2).all(|x| x[0] < x[1]));
+    /// assert!(sf.generate() > ids[9_999]);
+    /// ```
+    pub fn generate_batch(&self, n: usize) -> SnowflakeBatch {
+        match self.try_generate_batch(n) {
+            Ok(v) => v,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Generate `n` IDs at once, reserving a contiguous range of sequences in one atomic step,
+    /// fail if `n` is too large, the clock moved backwards and the policy refuses
+    /// to generate, or the timestamp is out of range of the layout. It's empty if `n` is 0.
+    pub fn try_generate_batch(&self, n: usize) -> Result<SnowflakeBatch, SnowflakeError> {
+        let count = i64::try_from(n).map_err(|_| SnowflakeError::InvalidBatchSize(n))?;
+        if count == 0 {
+            return Ok(SnowflakeBatch {
+                layout: self.layout,
+                node: self.node_bits(),
+                ts: 0,
+                seq: 0,
+                remaining: 0,
+            });
+        }
+        let mut ts = self.layout.ticks(self.millis_since_epoch());
+        if let Some(wait) = self.check_clock(&mut ts)? {
+            self.time_source.sleep(wait);
+        }
+        let (ts, seq) = self.reserve(ts, count, true)?.unwrap_or_default();
+        Ok(SnowflakeBatch {
+            layout: self.layout,
+            node: self.node_bits(),
+            ts,
+            seq,
+            remaining: n,
+        })
+    }
+
+    /// Generate a new ID asynchronously.
+    ///
+    /// Instead of borrowing the next time unit, it sleeps on the tokio timer
+    /// until the next time unit when the sequence space is used up.
+    /// A manually advanced time source must be advanced by others meanwhile.
+    #[cfg(feature = "tokio")]
+    pub async fn generate_async(&self) -> Result<i64, SnowflakeError> {
+        loop {
+            let mut ts = self.layout.ticks(self.millis_since_epoch());
+            if let Some(wait) = self.check_clock(&mut ts)? {
+                tokio::time::sleep(wait).await;
+            }
+            match self.reserve(ts, 1, false)? {
+                Some((ts, seq)) => break Ok(self.layout.compose(ts, self.node_bits(), seq)),
+                None => {
+                    self.sequence_exhaustions.fetch_add(1, Ordering::Relaxed);
+                    let last_ts = self.id.load(Ordering::Acquire) >> self.layout.timestamp_shift();
+                    let wait = (last_ts + 1) * self.layout.time_unit.as_millis()
+                        - self.millis_since_epoch();
+                    tokio::time::sleep(Duration::from_millis(wait.max(1) as u64)).await;
+                }
+            }
+        }
+    }
+
+    /// Check if the clock moved backwards, and apply the policy.
+    ///
+    /// Returns the duration to wait for if the policy is [`ClockRegressionPolicy::Wait`].
+    fn check_clock(&self, ts: &mut i64) -> Result<Option<Duration>, SnowflakeError> {
+        let unit = self.layout.time_unit.as_millis();
+        let clock = self.clock.fetch_max(*ts, Ordering::AcqRel);
+        if *ts < clock {
+            self.clock_regressions.fetch_add(1, Ordering::Relaxed);
+            match self.clock_policy {
+                ClockRegressionPolicy::Wait => {
+                    let wait = Duration::from_millis(((clock - *ts) * unit) as u64);
+                    *ts = clock;
+                    return Ok(Some(wait));
+                }
+                ClockRegressionPolicy::Error => {
+                    return Err(SnowflakeError::ClockMovedBackwards {
+                        last: clock * unit,
+                        now: *ts * unit,
+                    });
+                }
+                // Only the regression of the clock is limited, not the units borrowed by sequences.
+                ClockRegressionPolicy::Borrow(max_millis) => {
+                    if clock - *ts > max_millis / unit {
+                        return Err(SnowflakeError::ClockMovedBackwards {
+                            last: clock * unit,
+                            now: *ts * unit,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Reserve `n` sequences from the time `ts` in ticks, returns the first time and sequence.
+    ///
+    /// If `borrow` is `false`, returns `None` instead of borrowing the next time units
+    /// when the sequence space is used up.
+    /// Fails if the last time exceeds the maximum timestamp of the layout.
+    fn reserve(&self, ts: i64, n: i64, borrow: bool) -> Result<Option<(i64, i64)>, SnowflakeError> {
+        let layout = &self.layout;
+        let unit = layout.time_unit.as_millis();
+        let space = layout.max_sequence() + 1;
+        loop {
+            // Get a copy.
+            let last_id = self.id.load(Ordering::Acquire);
+            // Parse timestamp
+            let last_ts = last_id >> layout.timestamp_shift();
+            // Parse sequence and increase 1.
+            let (start_ts, start_seq) = if ts <= last_ts {
+                (last_ts, (last_id & layout.max_sequence()) + 1)
+            } else {
+                (ts, 0)
+            };
+
+            // The sequences out of space borrow the next time units.
+            let out_of_range =
+                || SnowflakeError::TimestampOutOfRange(start_ts.saturating_mul(unit));
+            let end = start_seq.checked_add(n - 1).ok_or_else(out_of_range)?;
+            let borrowed = end / space;
+            let end_ts = start_ts
+                .checked_add(borrowed)
+                .filter(|x| *x <= layout.max_timestamp())
+                .ok_or_else(out_of_range)?;
+            if borrowed > 0 && !borrow {
+                return Ok(None);
+            }
+            let id = layout.compose(end_ts, self.node_bits(), end % space);
+            if self
+                .id
+                .compare_exchange(last_id, id, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+            {
+                if borrowed > 0 {
+                    self.sequence_exhaustions
+                        .fetch_add(borrowed as u64, Ordering::Relaxed);
+                }
+                break Ok(Some(if start_seq < space {
+                    (start_ts, start_seq)
+                } else {
+                    (start_ts + 1, 0)
+                }));
+            }
+        }
+    }
+
+    /// Split an ID generated by this generator into its parts.
+    #[inline]
+    pub fn decode(&self, id: i64) -> SnowflakeParts {
+        self.layout.decode(id)
+    }
+
+    /// Get the generation time of an ID, based on the configured `epoch`.
+    #[cfg(feature = "datetime")]
+    #[inline]
+    pub fn to_utc(&self, id: i64) -> DateTimeUtc {
+        self.decode(id).to_utc(self.epoch)
+    }
+
+    #[inline]
+    fn node_bits(&self) -> i64 {
+        (self.worker_id << self.layout.worker_shift())
+            | (self.datacenter_id << self.layout.datacenter_shift())
+    }
+}
+
+/// Trait for the clock of Snowflake generators.
+pub trait TimeSource {
+    /// Get milliseconds duration since the Unix Epoch.
+    fn now_millis(&self) -> i64;
+
+    /// Block the current thread for the duration, waiting for the clock to catch up.
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+impl<T: TimeSource + ?Sized> TimeSource for Arc<T> {
+    #[inline]
+    fn now_millis(&self) -> i64 {
+        (**self).now_millis()
+    }
+
+    #[inline]
+    fn sleep(&self, duration: Duration) {
+        (**self).sleep(duration)
+    }
+}
+
+/// The system wall clock, which may be stepped backwards or forwards.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl TimeSource for SystemClock {
+    fn now_millis(&self) -> i64 {
+        match SystemTime::now().duration_since(UNIX_EPOCH) {
+            Ok(v) => v.as_millis() as i64,
+            _ => 0,
+        }
+    }
+}
+
+/// A monotonic clock anchored to the system wall clock at creation,
+/// it never goes backwards but may drift from the wall clock over time.
+#[derive(Clone, Copy, Debug)]
+pub struct MonotonicClock {
+    anchor_millis: i64,
+    anchor: Instant,
+}
+
+impl Default for MonotonicClock {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MonotonicClock {
+    /// Create a clock anchored to the current system time.
+    pub fn new() -> Self {
+        Self {
+            anchor_millis: SystemClock.now_millis(),
+            anchor: Instant::now(),
+        }
+    }
+}
+
+impl TimeSource for MonotonicClock {
+    #[inline]
+    fn now_millis(&self) -> i64 {
+        self.anchor_millis + self.anchor.elapsed().as_millis() as i64
+    }
+}
+
+/// A manually advanced clock for tests, sleeping on it advances the time instantly.
+#[derive(Debug, Default)]
+pub struct MockClock {
+    millis: AtomicI64,
+}
+
+impl MockClock {
+    /// Create a clock with the milliseconds since the Unix Epoch.
+    pub fn new(millis: i64) -> Self {
+        Self {
+            millis: AtomicI64::new(millis),
+        }
+    }
+
+    /// Set the milliseconds since the Unix Epoch, it may go backwards.
+    #[inline]
+    pub fn set(&self, millis: i64) {
+        self.millis.store(millis, Ordering::Release);
+    }
+
+    /// Advance the clock by milliseconds, a negative value moves it backwards.
+    #[inline]
+    pub fn advance(&self, millis: i64) {
+        self.millis.fetch_add(millis, Ordering::AcqRel);
+    }
+}
+
+impl TimeSource for MockClock {
+    #[inline]
+    fn now_millis(&self) -> i64 {
+        self.millis.load(Ordering::Acquire)
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration.as_millis() as i64);
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Iterator of IDs reserved by [`Snowflake::generate_batch`].
+#[derive(Clone, Debug)]
+pub struct SnowflakeBatch {
+    layout: SnowflakeLayout,
+    node: i64,
+    ts: i64,
+    seq: i64,
+    remaining: usize,
+}
+
+impl Iterator for SnowflakeBatch {
+    type Item = i64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let id = self.layout.compose(self.ts, self.node, self.seq);
+        if self.seq < self.layout.max_sequence() {
+            self.seq += 1;
+        } else {
+            self.ts += 1;
+            self.seq = 0;
+        }
+        Some(id)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for SnowflakeBatch {}
+
+/// The policy to handle the wall clock moving backwards, e.g. stepped by NTP.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ClockRegressionPolicy {
+    /// Keep generating IDs from the last timestamp, as long as the clock is
+    /// at most the specified milliseconds behind it.
+    /// The sequences out of space borrow the next time units in any case.
+    Borrow(i64),
+    /// Block the current thread until the clock catches up.
+    Wait,
+    /// Return [`SnowflakeError::ClockMovedBackwards`].
+    Error,
+}
+
+impl Default for ClockRegressionPolicy {
+    /// Borrow from the future without limit.
+    #[inline]
+    fn default() -> Self {
+        Self::Borrow(i64::MAX)
+    }
+}
+
+/// Counters of abnormal events of a Snowflake generator.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SnowflakeStats {
+    /// How many times the clock was found moving backwards.
+    pub clock_regressions: u64,
+    /// How many times the sequence space of a time unit was used up.
+    pub sequence_exhaustions: u64,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// The time unit of the timestamp field.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SnowflakeTimeUnit {
+    /// 1 millisecond.
+    #[default]
+    Millis,
+    /// 10 milliseconds.
+    Millis10,
+}
+
+impl SnowflakeTimeUnit {
+    /// Get the length of the time unit in milliseconds.
+    #[inline]
+    pub const fn as_millis(self) -> i64 {
+        match self {
+            Self::Millis => 1,
+            Self::Millis10 => 10,
+        }
+    }
+}
+
+/// The bit layout of Snowflake IDs, from the highest bits to the lowest:
+/// timestamp, worker ID, datacenter ID and sequence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SnowflakeLayout {
+    timestamp_bits: u8,
+    worker_bits: u8,
+    datacenter_bits: u8,
+    sequence_bits: u8,
+    time_unit: SnowflakeTimeUnit,
+}
+
+impl Default for SnowflakeLayout {
+    #[inline]
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+impl SnowflakeLayout {
+    /// The standard 41/5/5/12 bits layout in milliseconds.
+    pub const STANDARD: Self = Self {
+        timestamp_bits: 41,
+        worker_bits: 5,
+        datacenter_bits: 5,
+        sequence_bits: 12,
+        time_unit: SnowflakeTimeUnit::Millis,
+    };
+
+    /// Create a layout with the standard settings.
+    #[inline]
+    pub const fn new() -> Self {
+        Self::STANDARD
+    }
+
+    pub const fn with_timestamp_bits(mut self, bits: u8) -> Self {
+        self.timestamp_bits = bits;
+        self
+    }
+
+    pub const fn with_worker_bits(mut self, bits: u8) -> Self {
+        self.worker_bits = bits;
+        self
+    }
+
+    pub const fn with_datacenter_bits(mut self, bits: u8) -> Self {
+        self.datacenter_bits = bits;
+        self
+    }
+
+    pub const fn with_sequence_bits(mut self, bits: u8) -> Self {
+        self.sequence_bits = bits;
+        self
+    }
+
+    pub const fn with_time_unit(mut self, time_unit: SnowflakeTimeUnit) -> Self {
+        self.time_unit = time_unit;
+        self
+    }
+
+    /// Check if all fields fit in a positive `i64`.
+    pub fn validate(&self) -> Result<(), SnowflakeError> {
+        let total = self.timestamp_bits as u32
+            + self.worker_bits as u32
+            + self.datacenter_bits as u32
+            + self.sequence_bits as u32;
+        if self.timestamp_bits == 0 || self.sequence_bits == 0 || total > 63 {
+            Err(SnowflakeError::InvalidLayout(*self))
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline]
+    pub const fn timestamp_bits(&self) -> u8 {
+        self.timestamp_bits
+    }
+
+    #[inline]
+    pub const fn worker_bits(&self) -> u8 {
+        self.worker_bits
+    }
+
+    #[inline]
+    pub const fn datacenter_bits(&self) -> u8 {
+        self.datacenter_bits
+    }
+
+    #[inline]
+    pub const fn sequence_bits(&self) -> u8 {
+        self.sequence_bits
+    }
+
+    #[inline]
+    pub const fn time_unit(&self) -> SnowflakeTimeUnit {
+        self.time_unit
+    }
+
+    #[inline]
+    pub const fn max_timestamp(&self) -> i64 {
+        (1i64 << self.timestamp_bits) - 1
+    }
+
+    #[inline]
+    pub const fn max_worker_id(&self) -> i64 {
+        (1i64 << self.worker_bits) - 1
+    }
+
+    #[inline]
+    pub const fn max_datacenter_id(&self) -> i64 {
+        (1i64 << self.datacenter_bits) - 1
+    }
+
+    #[inline]
+    pub const fn max_sequence(&self) -> i64 {
+        (1i64 << self.sequence_bits) - 1
+    }
+
+    /// Split an ID into its parts.
+    pub fn decode(&self, id: i64) -> SnowflakeParts {
+        SnowflakeParts {
+            timestamp: (id >> self.timestamp_shift()) * self.time_unit.as_millis(),
+            worker_id: (id >> self.worker_shift()) & self.max_worker_id(),
+            datacenter_id: (id >> self.datacenter_shift()) & self.max_datacenter_id(),
+            sequence: id & self.max_sequence(),
+        }
+    }
+
+    /// Join the parts into an ID, the fields out of range are truncated.
+    pub fn encode(&self, parts: &SnowflakeParts) -> i64 {
+        self.compose(
+            self.ticks(parts.timestamp),
+            ((parts.worker_id & self.max_worker_id()) << self.worker_shift())
+                | ((parts.datacenter_id & self.max_datacenter_id()) << self.datacenter_shift()),
+            parts.sequence & self.max_sequence(),
+        )
+    }
+
+    #[inline]
+    fn ticks(&self, time_millis: i64) -> i64 {
+        time_millis.div_euclid(self.time_unit.as_millis())
+    }
+
+    #[inline]
+    fn compose(&self, ticks: i64, node: i64, seq: i64) -> i64 {
+        (ticks << self.timestamp_shift()) | node | seq
+    }
+
+    #[inline]
+    const fn timestamp_shift(&self) -> u32 {
+        self.worker_shift() + self.worker_bits as u32
+    }
+
+    #[inline]
+    const fn worker_shift(&self) -> u32 {
+        self.datacenter_shift() + self.datacenter_bits as u32
+    }
+
+    #[inline]
+    const fn datacenter_shift(&self) -> u32 {
+        self.sequence_bits as u32
+    }
+}
+
+/// Errors of Snowflake generators.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SnowflakeError {
+    /// The bit widths do not fit in a positive `i64`.
+    InvalidLayout(SnowflakeLayout),
+    /// The worker ID does not fit in its field.
+    WorkerIdOutOfRange(i64),
+    /// The datacenter ID does not fit in its field.
+    DatacenterIdOutOfRange(i64),
+    /// The clock moved backwards, times are in milliseconds since the epoch.
+    ClockMovedBackwards { last: i64, now: i64 },
+    /// The string is not an ID in the encoding.
+    InvalidEncoding(String),
+    /// A negative ID has no string encoding.
+    NegativeId(i64),
+    /// The number of IDs in a batch is too large.
+    InvalidBatchSize(usize),
+    /// The time does not fit in the timestamp field, in milliseconds since the epoch.
+    TimestampOutOfRange(i64),
+}
+
+impl fmt::Display for SnowflakeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLayout(v) => write!(
+                f,
+                "invalid Snowflake layout {}/{}/{}/{}",
+                v.timestamp_bits, v.worker_bits, v.datacenter_bits, v.sequence_bits
+            ),
+            Self::WorkerIdOutOfRange(v) => write!(f, "Snowflake worker ID {} is out of range", v),
+            Self::DatacenterIdOutOfRange(v) => {
+                write!(f, "Snowflake datacenter ID {} is out of range", v)
+            }
+            Self::ClockMovedBackwards { last, now } => write!(
+                f,
+                "clock moved backwards by {} ms",
+                last.saturating_sub(*now)
+            ),
+            Self::InvalidEncoding(v) => write!(f, "invalid encoded Snowflake ID {:?}", v),
+            Self::NegativeId(v) => write!(f, "negative Snowflake ID {} can't be encoded", v),
+            Self::InvalidBatchSize(v) => write!(f, "invalid Snowflake batch size {}", v),
+            Self::TimestampOutOfRange(v) => {
+                write!(f, "Snowflake timestamp {} ms is out of range", v)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SnowflakeError {}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// The fields of a Snowflake ID.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SnowflakeParts {
+    /// Milliseconds since the epoch of the generator.
+    pub timestamp: i64,
+    pub worker_id: i64,
+    pub datacenter_id: i64,
+    pub sequence: i64,
+}
+
+impl SnowflakeParts {
+    /// Split an ID with the standard layout into its parts.
+    #[inline]
+    pub fn decode(id: i64) -> Self {
+        SnowflakeLayout::STANDARD.decode(id)
+    }
+
+    /// Join the parts into an ID with the standard layout.
+    #[inline]
+    pub fn encode(&self) -> i64 {
+        SnowflakeLayout::STANDARD.encode(self)
+    }
+
+    /// Get the UNIX timestamp in milliseconds.
+    #[inline]
+    pub fn unix_millis(&self, epoch: i64) -> i64 {
+        epoch + self.timestamp
+    }
+
+    /// Convert the timestamp into `DateTimeUtc`.
+    #[cfg(feature = "datetime")]
+    pub fn to_utc(&self, epoch: i64) -> DateTimeUtc {
+        chrono::Utc
+            .timestamp_millis_opt(self.unix_millis(epoch))
+            .single()
+            .unwrap_or_else(utc_default)
+    }
+}
+
+impl From<i64> for SnowflakeParts {
+    #[inline]
+    fn from(id: i64) -> Self {
+        Self::decode(id)
+    }
+}
+
+impl From<SnowflakeParts> for i64 {
+    #[inline]
+    fn from(parts: SnowflakeParts) -> Self {
+        parts.encode()
+    }
+}
+
+impl fmt::Display for SnowflakeParts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "timestamp={}, worker_id={}, datacenter_id={}, sequence={}",
+            self.timestamp, self.worker_id, self.datacenter_id, self.sequence
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snowflake_parts() {
+        let sf = Snowflake::new(Snowflake::EPOCH_2026, 3, 17);
+        let id = sf.generate_with_time(123_456_789);
+        let parts = sf.decode(id);
+        assert_eq!(
+            parts,
+            SnowflakeParts {
+                timestamp: 123_456_789,
+                worker_id: 3,
+                datacenter_id: 17,
+                sequence: 0,
+            }
+        );
+        assert_eq!(parts.encode(), id);
+        assert_eq!(sf.decode(sf.generate_with_time(0)).sequence, 1);
+        assert_eq!(
+            parts.to_string(),
+            "timestamp=123456789, worker_id=3, datacenter_id=17, sequence=0"
+        );
+
+        #[cfg(feature = "datetime")]
+        assert_eq!(
+            sf.to_utc(id).timestamp_millis(),
+            Snowflake::EPOCH_2026 + 123_456_789
+        );
+
+        #[cfg(feature = "serde")]
+        {
+            let jsn = serde_json::to_value(parts).unwrap();
+            assert_eq!(jsn["worker_id"], 3);
+            assert_eq!(
+                serde_json::from_value::<SnowflakeParts>(jsn).unwrap(),
+                parts
+            );
+        }
+    }
+
+    #[test]
+    fn test_snowflake_layout() {
+        assert_eq!(
+            Snowflake::try_new(0, 32, 0).err(),
+            Some(SnowflakeError::WorkerIdOutOfRange(32))
+        );
+        assert_eq!(
+            Snowflake::try_new(0, 0, -1).err(),
+            Some(SnowflakeError::DatacenterIdOutOfRange(-1))
+        );
+        let layout = SnowflakeLayout::new().with_timestamp_bits(42);
+        assert!(Snowflake::with_layout(layout, 0, 0, 0).is_err());
+
+        let layout = SnowflakeLayout::new()
+            .with_worker_bits(10)
+            .with_datacenter_bits(0)
+            .with_sequence_bits(7)
+            .with_time_unit(SnowflakeTimeUnit::Millis10);
+        let sf = Snowflake::with_layout(layout, Snowflake::EPOCH_2022, 1000, 0).unwrap();
+        assert_eq!((sf.worker_id(), sf.datacenter_id()), (1000, 0));
+        let id = sf.generate_with_time(12_345);
+        assert_eq!(id, (1234 << 17) | (1000 << 7));
+        let parts = sf.decode(id);
+        assert_eq!((parts.timestamp, parts.worker_id), (12_340, 1000));
+        assert_eq!(layout.encode(&parts), id);
+
+        // The sequence overflows into the next time unit.
+        for _ in 0..layout.max_sequence() {
+            sf.generate_with_time(12_345);
+        }
+        assert_eq!(sf.decode(sf.generate_with_time(12_345)).timestamp, 12_350);
+    }
+
+    #[test]
+    fn test_snowflake_batch() {
+        let sf = Snowflake::new(0, 1, 1);
+        let ts = sf.decode(sf.generate()).timestamp;
+        let ids: Vec<i64> = sf.generate_batch(5000).collect();
+        assert_eq!(ids.len(), 5000);
+        assert!(ids.windows(2).all(|x| x[0] < x[1]));
+        assert!(sf.decode(ids[0]).timestamp >= ts);
+        assert_eq!(sf.decode(ids[0]).worker_id, 1);
+        assert!(sf.generate() > ids[4999]);
+        assert_eq!(sf.generate_batch(0).count(), 0);
+        assert_eq!(
+            sf.try_generate_batch(usize::MAX).err(),
+            Some(SnowflakeError::InvalidBatchSize(usize::MAX))
+        );
+
+        // The borrowed time units must fit in the timestamp field.
+        let layout = SnowflakeLayout::new().with_timestamp_bits(10);
+        let sf = Snowflake::with_layout(layout, 0, 1, 1)
+            .unwrap()
+            .with_time_source(MockClock::new(1022));
+        assert_eq!(sf.generate_batch(4096).len(), 4096);
+        assert_eq!(sf.decode(sf.generate()).timestamp, 1023);
+        assert_eq!(
+            sf.try_generate_batch(5000).err(),
+            Some(SnowflakeError::TimestampOutOfRange(1023))
+        );
+        assert_eq!(
+            sf.try_generate_batch(i64::MAX as usize).err(),
+            sf.try_generate_batch(5000).err()
+        );
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_snowflake_async() {
+        let layout = SnowflakeLayout::new().with_sequence_bits(2);
+        let sf = Snowflake::with_layout(layout, 0, 1, 1).unwrap();
+        let mut ids = Vec::new();
+        for _ in 0..10 {
+            ids.push(sf.generate_async().await.unwrap());
+        }
+        assert!(ids.windows(2).all(|x| x[0] < x[1]));
+        assert!(sf.stats().sequence_exhaustions >= 2);
+        // No ID is borrowed from the future.
+        assert!(sf.decode(ids[9]).timestamp <= sf.millis_since_epoch());
+    }
+
+    #[test]
+    fn test_snowflake_clock_policy() {
+        let sf = Snowflake::new(0, 1, 1);
+        sf.generate_with_time(1000);
+        assert_eq!(sf.decode(sf.generate_with_time(900)).timestamp, 1000);
+        assert_eq!(sf.stats().clock_regressions, 1);
+
+        let sf = Snowflake::new(0, 1, 1).with_clock_policy(ClockRegressionPolicy::Error);
+        sf.generate_with_time(1000);
+        assert_eq!(
+            sf.try_generate_with_time(900),
+            Err(SnowflakeError::ClockMovedBackwards {
+                last: 1000,
+                now: 900
+            })
+        );
+        assert!(sf.try_generate_with_time(1000).is_ok());
+
+        let sf = Snowflake::new(0, 1, 1).with_clock_policy(ClockRegressionPolicy::Borrow(50));
+        sf.generate_with_time(1000);
+        assert!(sf.try_generate_with_time(960).is_ok());
+        assert!(sf.try_generate_with_time(940).is_err());
+        assert_eq!(sf.stats().clock_regressions, 2);
+
+        // Running out of sequences is not a regression of the clock.
+        let sf = Snowflake::new(0, 1, 1).with_clock_policy(ClockRegressionPolicy::Borrow(0));
+        for _ in 0..=SnowflakeLayout::STANDARD.max_sequence() {
+            sf.generate_with_time(1000);
+        }
+        assert_eq!(sf.decode(sf.generate_with_time(1000)).timestamp, 1001);
+        // The clock is still at 1000, after the sequences borrowed 1001.
+        assert_eq!(sf.decode(sf.generate_with_time(1000)).timestamp, 1001);
+        let sf = sf.with_time_source(MockClock::new(2000));
+        assert_eq!(sf.generate_batch(10_000).len(), 10_000);
+        assert!(sf.try_generate().is_ok());
+        assert_eq!(sf.stats().clock_regressions, 0);
+
+        let sf = Snowflake::new(0, 1, 1)
+            .with_clock_policy(ClockRegressionPolicy::Borrow(5))
+            .with_time_source(MockClock::new(2000));
+        assert_eq!(sf.generate_batch(100_000).len(), 100_000);
+        assert!(sf.decode(sf.try_generate().unwrap()).timestamp > 2005);
+        sf.time_source().advance(-5);
+        assert!(sf.try_generate().is_ok());
+        sf.time_source().advance(-1);
+        assert_eq!(
+            sf.try_generate(),
+            Err(SnowflakeError::ClockMovedBackwards {
+                last: 2000,
+                now: 1994
+            })
+        );
+        assert_eq!(sf.stats().clock_regressions, 2);
+
+        let sf = Snowflake::new(0, 1, 1).with_clock_policy(ClockRegressionPolicy::Wait);
+        sf.generate_with_time(1000);
+        let now = std::time::Instant::now();
+        assert_eq!(sf.decode(sf.generate_with_time(990)).timestamp, 1000);
+        assert!(now.elapsed() >= Duration::from_millis(10));
+
+        let sf = Snowflake::new(0, 1, 1);
+        for _ in 0..=SnowflakeLayout::STANDARD.max_sequence() {
+            sf.generate_with_time(1000);
+        }
+        assert_eq!(sf.stats().sequence_exhaustions, 0);
+        assert_eq!(sf.decode(sf.generate_with_time(1000)).timestamp, 1001);
+        assert_eq!(
+            sf.stats(),
+            SnowflakeStats {
+                clock_regressions: 0,
+                sequence_exhaustions: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_snowflake_time_source() {
+        let clock = Arc::new(MockClock::new(10_000));
+        let sf = Snowflake::new(0, 1, 1).with_time_source(clock.clone());
+        for _ in 0..=SnowflakeLayout::STANDARD.max_sequence() {
+            assert_eq!(sf.decode(sf.generate()).timestamp, 10_000);
+        }
+        // The sequence rolls over into the next millisecond.
+        assert_eq!(sf.decode(sf.generate()).timestamp, 10_001);
+        clock.advance(5);
+        assert_eq!(sf.decode(sf.generate()).sequence, 0);
+
+        // Waiting on a regression advances the mock clock.
+        let sf = sf.with_clock_policy(ClockRegressionPolicy::Wait);
+        sf.generate();
+        clock.set(9_000);
+        assert_eq!(sf.decode(sf.generate()).timestamp, 10_005);
+        assert_eq!(clock.now_millis(), 10_005);
+        assert_eq!(sf.stats().clock_regressions, 1);
+
+        let mono = MonotonicClock::new();
+        assert!((mono.now_millis() - Snowflake::now_millis()).abs() < 1000);
+    }
+}